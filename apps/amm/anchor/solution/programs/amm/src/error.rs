@@ -8,4 +8,6 @@ pub enum Error {
     DecimalsMismatch,
     #[msg("Amount out < min")]
     MinAmountOut,
+    #[msg("Math overflow")]
+    MathOverflow,
 }
@@ -6,6 +6,7 @@ use anchor_spl::{
 
 use super::lib;
 use crate::constants;
+use crate::error;
 use crate::state::Pool;
 
 #[derive(Accounts)]
@@ -93,20 +94,19 @@ pub fn add_liquidity(
     Calculate user shares to mint
     user shares = user_liquidity / pool_liquidity * supply
     user_liquidity = amount_a + amount_b
+
+    Shares minted are rounded down.
+    Intermediate values are u128 so that large supplies and reserves don't overflow.
     */
-    let user_liquidity = amount_a.checked_add(amount_b).unwrap();
-    let pool_liquidity = ctx
-        .accounts
-        .pool_a
-        .amount
-        .checked_add(ctx.accounts.pool_b.amount)
-        .unwrap();
-    let supply = ctx.accounts.mint_pool.supply;
+    let user_liquidity = (amount_a as u128) + (amount_b as u128);
+    let pool_liquidity = (ctx.accounts.pool_a.amount as u128)
+        + (ctx.accounts.pool_b.amount as u128);
+    let supply = ctx.accounts.mint_pool.supply as u128;
 
     let shares = if pool_liquidity > 0 {
-        user_liquidity.checked_mul(supply).unwrap() / pool_liquidity
+        lib::mul_div_down(user_liquidity, supply, pool_liquidity)?
     } else {
-        user_liquidity
+        u64::try_from(user_liquidity).map_err(|_| error::Error::MathOverflow)?
     };
 
     // Transfer amount_a from user into pool_a
@@ -5,6 +5,7 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+use crate::error;
 use crate::state::Pool;
 
 pub fn transfer<'info>(
@@ -90,3 +91,15 @@ pub fn burn<'info>(
         amount,
     )
 }
+
+// Calculate a * b / c, rounding down.
+// Use for amounts paid out by the pool.
+pub fn mul_div_down(a: u128, b: u128, c: u128) -> Result<u64> {
+    let q = a
+        .checked_mul(b)
+        .ok_or(error::Error::MathOverflow)?
+        .checked_div(c)
+        .ok_or(error::Error::MathOverflow)?;
+
+    Ok(u64::try_from(q).map_err(|_| error::Error::MathOverflow)?)
+}
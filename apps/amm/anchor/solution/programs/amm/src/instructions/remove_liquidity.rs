@@ -96,17 +96,20 @@ pub fn remove_liquidity(
     shares / supply = (amount_a + amount_b) / (pool_a + pool_b)
     amount_a = shares / supply * pool_a_amount
     amount_b = shares / supply * pool_b_amount
+
+    Amounts paid out are rounded down.
     */
-    let amount_a = shares
-        .checked_mul(ctx.accounts.pool_a.amount)
-        .unwrap()
-        .checked_div(ctx.accounts.mint_pool.supply)
-        .unwrap();
-    let amount_b = shares
-        .checked_mul(ctx.accounts.pool_b.amount)
-        .unwrap()
-        .checked_div(ctx.accounts.mint_pool.supply)
-        .unwrap();
+    let supply = ctx.accounts.mint_pool.supply as u128;
+    let amount_a = lib::mul_div_down(
+        shares as u128,
+        ctx.accounts.pool_a.amount as u128,
+        supply,
+    )?;
+    let amount_b = lib::mul_div_down(
+        shares as u128,
+        ctx.accounts.pool_b.amount as u128,
+        supply,
+    )?;
 
     // Check amount_a >= min_amount_a
     // Check amount_b >= min_amount_b
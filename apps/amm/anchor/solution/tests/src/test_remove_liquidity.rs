@@ -146,3 +146,187 @@ fn test_remove_liquidity() {
         "pool amount b"
     );
 }
+
+#[test]
+fn test_remove_liquidity_near_u64_max() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        users,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        fee,
+        atas_a,
+        atas_b,
+        atas_pool,
+    } = test_helper::set_up(&payer);
+
+    // Init
+    program
+        .request()
+        .accounts(amm::accounts::InitPool {
+            payer: users[0].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[0])
+        .args(amm::instruction::InitPool { fee })
+        .send()
+        .unwrap();
+
+    // Add liquidity so that supply and reserves are close to u64::MAX / 2
+    let amount_a = u64::MAX / 4;
+    let amount_b = u64::MAX / 4;
+
+    token_helper::mint_to(
+        &token_program,
+        &payer,
+        &mint_a,
+        &atas_a[0],
+        amount_a,
+    )
+    .unwrap();
+    token_helper::mint_to(
+        &token_program,
+        &payer,
+        &mint_b,
+        &atas_b[0],
+        amount_b,
+    )
+    .unwrap();
+
+    program
+        .request()
+        .accounts(amm::accounts::AddLiquidity {
+            payer: users[0].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            payer_a: atas_a[0],
+            payer_b: atas_b[0],
+            payer_liquidity: atas_pool[0],
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[0])
+        .args(amm::instruction::AddLiquidity {
+            fee,
+            amount_a,
+            amount_b,
+        })
+        .send()
+        .unwrap();
+
+    // user_liquidity * supply > u64::MAX
+    let amount = 10 * (1e6 as u64);
+
+    program
+        .request()
+        .accounts(amm::accounts::AddLiquidity {
+            payer: users[1].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            payer_a: atas_a[1],
+            payer_b: atas_b[1],
+            payer_liquidity: atas_pool[1],
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[1])
+        .args(amm::instruction::AddLiquidity {
+            fee,
+            amount_a: amount,
+            amount_b: amount,
+        })
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &atas_pool[1]).unwrap(),
+        amount + amount,
+        "user 1 shares"
+    );
+
+    // shares * pool_a_amount > u64::MAX
+    let shares =
+        token_helper::get_balance(&token_program, &atas_pool[0]).unwrap();
+    assert_eq!(shares, amount_a + amount_b, "user 0 shares");
+
+    let user_a_bal_before =
+        token_helper::get_balance(&token_program, &atas_a[0]).unwrap();
+    let user_b_bal_before =
+        token_helper::get_balance(&token_program, &atas_b[0]).unwrap();
+
+    program
+        .request()
+        .accounts(amm::accounts::RemoveLiquidity {
+            payer: users[0].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            payer_a: atas_a[0],
+            payer_b: atas_b[0],
+            payer_liquidity: atas_pool[0],
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[0])
+        .args(amm::instruction::RemoveLiquidity {
+            fee,
+            shares,
+            min_amount_a: amount_a,
+            min_amount_b: amount_b,
+        })
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &atas_a[0]).unwrap(),
+        user_a_bal_before + amount_a,
+        "user a balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &atas_b[0]).unwrap(),
+        user_b_bal_before + amount_b,
+        "user b balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &pool_a).unwrap(),
+        amount,
+        "pool amount a"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &pool_b).unwrap(),
+        amount,
+        "pool amount b"
+    );
+}
@@ -91,22 +91,16 @@ pub fn add_liquidity(
     let supply = mint_pool_account.supply;
 
     // Calculate shares to mint
-    let user_liquidity = amount_a
-        .checked_add(amount_b)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let pool_liquidity = pool_a_amount
-        .checked_add(pool_b_amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    // shares = user_liquidity * supply / pool_liquidity, rounded down
+    // Intermediate values are u128 so that large supplies and reserves don't overflow
+    let user_liquidity = (amount_a as u128) + (amount_b as u128);
+    let pool_liquidity = (pool_a_amount as u128) + (pool_b_amount as u128);
 
     let shares = if pool_liquidity > 0 {
-        user_liquidity
-            .checked_mul(supply)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(pool_liquidity)
-            .ok_or(ProgramError::ArithmeticOverflow)?
+        lib::mul_div_down(user_liquidity, supply as u128, pool_liquidity)?
     } else {
-        user_liquidity
+        u64::try_from(user_liquidity)
+            .map_err(|_| ProgramError::ArithmeticOverflow)?
     };
 
     // Initialize payer_liq (associated token account for mint_pool owned by payer) if not initialized.
@@ -380,3 +380,15 @@ pub fn get_decimals<'a>(mint: &AccountInfo<'a>) -> Result<u8, ProgramError> {
 
     Ok(mint_data.decimals)
 }
+
+// Calculate a * b / c, rounding down.
+// Use for amounts paid out by the pool.
+pub fn mul_div_down(a: u128, b: u128, c: u128) -> Result<u64, ProgramError> {
+    let q = a
+        .checked_mul(b)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .checked_div(c)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    u64::try_from(q).map_err(|_| ProgramError::ArithmeticOverflow)
+}
@@ -2,7 +2,6 @@ use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
-    program_error::ProgramError,
     pubkey::Pubkey,
 };
 use solana_program_pack::Pack;
@@ -91,17 +90,18 @@ pub fn remove_liquidity(
     // Calculate amounts of token A and B to withdraw
     // amount_a = shares * pool_a_amount / supply
     // amount_b = shares * pool_b_amount / supply
-    let amount_a = shares
-        .checked_mul(pool_a_amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?
-        .checked_div(supply)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let amount_b = shares
-        .checked_mul(pool_b_amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?
-        .checked_div(supply)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    // Amounts paid out are rounded down
+    let amount_a = lib::mul_div_down(
+        shares as u128,
+        pool_a_amount as u128,
+        supply as u128,
+    )?;
+
+    let amount_b = lib::mul_div_down(
+        shares as u128,
+        pool_b_amount as u128,
+        supply as u128,
+    )?;
 
     // Check amounts to withdraw are greater or equal to minimum specified by user
     assert!(amount_a >= min_amount_a, "amount_a < min");
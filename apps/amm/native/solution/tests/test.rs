@@ -3,12 +3,14 @@ use litesvm::LiteSVM;
 use solana_program::sysvar::clock::Clock;
 use solana_sdk::{signature::Signer, transaction::Transaction};
 
+use amm::instructions::lib::mul_div_down;
 use amm::state::Pool;
 
 mod helper;
 use helper::{
     Test, create_add_liquidity_ix, create_init_pool_ix,
-    create_remove_liquidity_ix, create_swap_ix, get_token_balance, setup,
+    create_remove_liquidity_ix, create_swap_ix, get_token_balance, mint_to,
+    setup,
 };
 
 #[test]
@@ -387,3 +389,179 @@ fn test_swap() {
     assert!(pool_a_bal_before < pool_a_bal_after);
     assert!(pool_b_bal_before > pool_b_bal_after);
 }
+
+#[test]
+fn test_mul_div() {
+    let max = u64::MAX as u128;
+
+    assert_eq!(mul_div_down(max, max, max).unwrap(), u64::MAX);
+
+    // Rounding direction
+    assert_eq!(mul_div_down(max, 2, 3).unwrap(), u64::MAX / 3 * 2);
+    assert_eq!(mul_div_down(max - 1, 1, 2).unwrap(), u64::MAX / 2);
+    assert_eq!(mul_div_down(1, 1, 3).unwrap(), 0);
+
+    // Result doesn't fit in u64
+    assert!(mul_div_down(max, 2, 1).is_err());
+
+    // Division by zero
+    assert!(mul_div_down(1, 1, 0).is_err());
+}
+
+#[test]
+fn test_remove_liquidity_near_u64_max() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        users,
+        mint_a,
+        mint_b,
+        atas_a,
+        atas_b,
+        fee,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        atas_liq,
+    } = setup(&mut svm);
+
+    // Init pool
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity so that supply and reserves are close to u64::MAX / 2
+    let amt_a = u64::MAX / 4;
+    let amt_b = u64::MAX / 4;
+
+    mint_to(&mut svm, &payer, &mint_a, &atas_a[0], amt_a);
+    mint_to(&mut svm, &payer, &mint_b, &atas_b[0], amt_b);
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let shares_0 = get_token_balance(&svm, &atas_liq[0]);
+    assert_eq!(shares_0, amt_a + amt_b);
+
+    // user_liquidity * supply > u64::MAX
+    let amt = 1e9 as u64;
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt,
+        amt,
+        atas_a[1],
+        atas_b[1],
+        atas_liq[1],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(get_token_balance(&svm, &atas_liq[1]), amt + amt);
+
+    // shares * pool_a_amount > u64::MAX
+    let user_a_bal_before = get_token_balance(&svm, &atas_a[0]);
+    let user_b_bal_before = get_token_balance(&svm, &atas_b[0]);
+
+    let ix = create_remove_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        shares_0,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(
+        get_token_balance(&svm, &atas_a[0]),
+        user_a_bal_before + amt_a
+    );
+    assert_eq!(
+        get_token_balance(&svm, &atas_b[0]),
+        user_b_bal_before + amt_b
+    );
+    assert_eq!(get_token_balance(&svm, &atas_liq[0]), 0);
+    assert_eq!(get_token_balance(&svm, &pool_a), amt);
+    assert_eq!(get_token_balance(&svm, &pool_b), amt);
+}
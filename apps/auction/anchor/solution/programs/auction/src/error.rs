@@ -20,4 +20,10 @@ pub enum Error {
     InvalidCurrentPrice,
     #[msg("Max price")]
     MaxPrice,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Fee > max fee")]
+    InvalidFee,
+    #[msg("Not admin")]
+    NotAdmin,
 }
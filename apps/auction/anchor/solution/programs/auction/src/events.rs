@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub auction_id: u64,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub sell_amt: u64,
+}
+
+#[event]
+pub struct AuctionFilled {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    // Amount of sell token bought
+    pub amount: u64,
+    // Price of 1 sell token
+    pub price: u64,
+    // Amount of buy token paid, including fee
+    pub buy_amt: u64,
+    // Amount of sell token left, auction is closed when 0
+    pub remaining: u64,
+    pub timestamp: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    // Amount of sell token returned to seller
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+// Parameters changed by update, all values are after the update
+#[event]
+pub struct AuctionUpdated {
+    pub auction: Pubkey,
+    pub end_price: u64,
+    pub end_time: u64,
+    // Amount of sell token in auction_sell_ata
+    pub sell_amt: u64,
+    pub timestamp: u64,
+}
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{
+        create_idempotent, get_associated_token_address_with_program_id,
+        AssociatedToken, Create,
+    },
     token_interface::{
         close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
     },
@@ -8,6 +11,7 @@ use anchor_spl::{
 
 use super::lib;
 use crate::error;
+use crate::events;
 use crate::state;
 
 #[derive(Accounts)]
@@ -27,7 +31,8 @@ pub struct Buy<'info> {
             state::Auction::SEED_PREFIX,
             seller.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            auction.auction_id.to_le_bytes().as_ref()
         ],
         bump,
         close = seller,
@@ -62,6 +67,25 @@ pub struct Buy<'info> {
     )]
     pub seller_buy_ata: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: Protocol config, read by buy if init_config was called
+    #[account(
+        seeds = [state::Config::SEED_PREFIX],
+        bump,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Protocol fee vault for mint_buy, created by buy only when a
+    /// fee is charged
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &config.key(),
+            &mint_buy.key(),
+            &token_program.key(),
+        ),
+    )]
+    pub fee_vault: UncheckedAccount<'info>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -79,11 +103,12 @@ pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
     require!(now < auction.end_time, error::Error::AuctionEnded);
 
     // Calculate price
-    let price_decrease = (auction.start_price - auction.end_price)
-        * (now - auction.start_time)
-        / (auction.end_time - auction.start_time);
+    let price_decrease = (auction.start_price - auction.end_price) as u128
+        * (now - auction.start_time) as u128
+        / (auction.end_time - auction.start_time) as u128;
 
-    let price = auction.start_price - price_decrease;
+    // price_decrease <= start_price - end_price
+    let price = auction.start_price - price_decrease as u64;
 
     // Check current price is greater than or equal to end_price
     require!(
@@ -96,23 +121,51 @@ pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
 
     // Calculate amount of buy token to send to seller
     let sell_amt = ctx.accounts.auction_sell_ata.amount;
-    let buy_amt = sell_amt * price / (1e6 as u64);
-
-    // Send buy token to seller
+    let buy_amt = lib::get_buy_amt(sell_amt, price, auction.decimals_sell)?;
+
+    // Send protocol fee to fee_vault
+    let fee_bps = lib::get_fee_bps(&ctx.accounts.config)?;
+    let fee = lib::get_fee(buy_amt, fee_bps);
+    if fee > 0 {
+        // Create fee_vault on the first fee charged in mint_buy
+        create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.config.to_account_info(),
+                mint: ctx.accounts.mint_buy.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        lib::transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.buyer_buy_ata,
+            &ctx.accounts.fee_vault,
+            &ctx.accounts.buyer,
+            fee,
+        )?;
+    }
+
+    // Send rest of buy token to seller
     lib::transfer(
         &ctx.accounts.token_program,
         &ctx.accounts.buyer_buy_ata,
         &ctx.accounts.seller_buy_ata,
         &ctx.accounts.buyer,
-        buy_amt,
+        buy_amt - fee,
     )?;
 
     // Send sell token to buyer
+    let auction_id = ctx.accounts.auction.auction_id.to_le_bytes();
     let seeds: &[&[u8]] = &[
         state::Auction::SEED_PREFIX,
         &ctx.accounts.seller.key().to_bytes(),
         &ctx.accounts.mint_sell.key().to_bytes(),
         &ctx.accounts.mint_buy.key().to_bytes(),
+        &auction_id,
         &[ctx.bumps.auction],
     ];
 
@@ -136,5 +189,15 @@ pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
         &[seeds],
     ))?;
 
+    emit!(events::AuctionFilled {
+        auction: ctx.accounts.auction.key(),
+        buyer: ctx.accounts.buyer.key(),
+        amount: sell_amt,
+        price,
+        buy_amt,
+        remaining: 0,
+        timestamp: now,
+    });
+
     Ok(())
 }
@@ -8,6 +8,7 @@ use anchor_spl::{
 
 use super::lib;
 use crate::error;
+use crate::events;
 use crate::state;
 
 #[derive(Accounts)]
@@ -24,7 +25,8 @@ pub struct Cancel<'info> {
             state::Auction::SEED_PREFIX,
             payer.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            auction.auction_id.to_le_bytes().as_ref()
         ],
         bump,
         close = payer,
@@ -51,21 +53,28 @@ pub struct Cancel<'info> {
 }
 
 pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
+    let clock = Clock::get()?;
+    let now = u64::try_from(clock.unix_timestamp).unwrap();
+
     // Send sell token to seller
+    let auction_id = ctx.accounts.auction.auction_id.to_le_bytes();
     let seeds: &[&[u8]] = &[
         state::Auction::SEED_PREFIX,
         &ctx.accounts.payer.key().to_bytes(),
         &ctx.accounts.mint_sell.key().to_bytes(),
         &ctx.accounts.mint_buy.key().to_bytes(),
+        &auction_id,
         &[ctx.bumps.auction],
     ];
 
+    let sell_amt = ctx.accounts.auction_sell_ata.amount;
+
     lib::transfer_from_pda(
         &ctx.accounts.token_program,
         &ctx.accounts.auction_sell_ata,
         &ctx.accounts.seller_sell_ata,
         &ctx.accounts.auction,
-        ctx.accounts.auction_sell_ata.amount,
+        sell_amt,
         seeds,
     )?;
 
@@ -80,5 +89,12 @@ pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
         &[seeds],
     ))?;
 
+    emit!(events::AuctionCancelled {
+        auction: ctx.accounts.auction.key(),
+        seller: ctx.accounts.payer.key(),
+        amount: sell_amt,
+        timestamp: now,
+    });
+
     Ok(())
 }
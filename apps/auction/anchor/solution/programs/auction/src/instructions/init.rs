@@ -6,9 +6,11 @@ use anchor_spl::{
 
 use super::lib;
 use crate::error;
+use crate::events;
 use crate::state;
 
 #[derive(Accounts)]
+#[instruction(auction_id: u64)]
 pub struct Init<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -24,7 +26,8 @@ pub struct Init<'info> {
             state::Auction::SEED_PREFIX,
             payer.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            auction_id.to_le_bytes().as_ref()
         ],
         bump,
     )]
@@ -63,6 +66,8 @@ pub struct Init<'info> {
 
 pub fn init(
     ctx: Context<Init>,
+    // Chosen by seller, allows concurrent auctions of the same mints
+    auction_id: u64,
     start_price: u64,
     end_price: u64,
     start_time: u64,
@@ -99,12 +104,29 @@ pub fn init(
 
     // Store Auction state
     let auction = &mut ctx.accounts.auction;
+    auction.seller = ctx.accounts.payer.key();
+    auction.auction_id = auction_id;
     auction.mint_sell = ctx.accounts.mint_sell.key();
     auction.mint_buy = ctx.accounts.mint_buy.key();
+    auction.decimals_sell = ctx.accounts.mint_sell.decimals;
+    auction.decimals_buy = ctx.accounts.mint_buy.decimals;
     auction.start_price = start_price;
     auction.end_price = end_price;
     auction.start_time = start_time;
     auction.end_time = end_time;
 
+    emit!(events::AuctionCreated {
+        auction: auction.key(),
+        seller: auction.seller,
+        auction_id,
+        mint_sell: auction.mint_sell,
+        mint_buy: auction.mint_buy,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+    });
+
     Ok(())
 }
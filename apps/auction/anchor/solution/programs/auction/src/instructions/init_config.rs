@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    // Becomes the admin, must be the upgrade authority of this program
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + state::Config::INIT_SPACE,
+        seeds = [state::Config::SEED_PREFIX],
+        bump,
+    )]
+    pub config: Account<'info, state::Config>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()),
+    )]
+    pub program: Program<'info, crate::program::Auction>,
+
+    // Check payer is the upgrade authority, otherwise anyone could create
+    // the config first and become the admin
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ error::Error::NotAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_config(ctx: Context<InitConfig>, fee_bps: u16) -> Result<()> {
+    // Check fee_bps <= MAX_FEE_BPS
+    require!(
+        fee_bps <= state::Config::MAX_FEE_BPS,
+        error::Error::InvalidFee
+    );
+
+    // Store Config state
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.payer.key();
+    config.fee_bps = fee_bps;
+    config.bump = ctx.bumps.config;
+
+    Ok(())
+}
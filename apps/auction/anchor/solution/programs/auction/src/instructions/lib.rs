@@ -5,12 +5,14 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::Auction;
+use crate::error;
+use crate::state;
 
+// dst can be an account created in the same instruction, like fee_vault
 pub fn transfer<'info>(
     token_program: &Interface<'info, TokenInterface>,
     src: &InterfaceAccount<'info, TokenAccount>,
-    dst: &InterfaceAccount<'info, TokenAccount>,
+    dst: &impl ToAccountInfo<'info>,
     auth: &Signer<'info>,
     amt: u64,
 ) -> Result<()> {
@@ -31,7 +33,7 @@ pub fn transfer_from_pda<'info>(
     token_program: &Interface<'info, TokenInterface>,
     src: &InterfaceAccount<'info, TokenAccount>,
     dst: &InterfaceAccount<'info, TokenAccount>,
-    auth: &Account<'info, Auction>,
+    auth: &impl ToAccountInfo<'info>,
     amt: u64,
     seeds: &[&[u8]],
 ) -> Result<()> {
@@ -48,3 +50,38 @@ pub fn transfer_from_pda<'info>(
         amt,
     )
 }
+
+// Amount of buy token to pay for sell_amt at price
+// price = buy token units per 1 whole sell token (10^decimals_sell units)
+// Rounded up so that the seller never receives less than the price
+pub fn get_buy_amt(
+    sell_amt: u64,
+    price: u64,
+    decimals_sell: u8,
+) -> Result<u64> {
+    let one = 10u128
+        .checked_pow(decimals_sell as u32)
+        .ok_or(error::Error::MathOverflow)?;
+
+    let buy_amt = (sell_amt as u128 * price as u128).div_ceil(one);
+
+    u64::try_from(buy_amt).map_err(|_| error::Error::MathOverflow.into())
+}
+
+// Protocol fee on buy_amt, rounded down
+pub fn get_fee(buy_amt: u64, fee_bps: u16) -> u64 {
+    // buy_amt * fee_bps / 10000 <= buy_amt
+    (buy_amt as u128 * fee_bps as u128 / 10000) as u64
+}
+
+// Protocol fee of Config, 0 until the config is created by init_config
+pub fn get_fee_bps(config: &AccountInfo) -> Result<u16> {
+    if config.owner != &crate::ID {
+        return Ok(0);
+    }
+
+    let data = config.try_borrow_data()?;
+    let config = state::Config::try_deserialize(&mut &data[..])?;
+
+    Ok(config.fee_bps)
+}
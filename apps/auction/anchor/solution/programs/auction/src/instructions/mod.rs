@@ -1,8 +1,16 @@
 mod buy;
 mod cancel;
 mod init;
+mod init_config;
 mod lib;
+mod set_fee;
+mod update;
+mod withdraw_fees;
 
 pub use buy::*;
 pub use cancel::*;
 pub use init::*;
+pub use init_config::*;
+pub use set_fee::*;
+pub use update::*;
+pub use withdraw_fees::*;
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [state::Config::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, state::Config>,
+}
+
+pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // Check admin matches config
+    require!(
+        ctx.accounts.admin.key() == config.admin,
+        error::Error::NotAdmin
+    );
+    // Check fee_bps <= MAX_FEE_BPS
+    require!(
+        fee_bps <= state::Config::MAX_FEE_BPS,
+        error::Error::InvalidFee
+    );
+
+    config.fee_bps = fee_bps;
+
+    Ok(())
+}
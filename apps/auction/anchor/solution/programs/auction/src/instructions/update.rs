@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::lib;
+use crate::error;
+use crate::events;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint_sell: InterfaceAccount<'info, Mint>,
+    pub mint_buy: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            state::Auction::SEED_PREFIX,
+            payer.key().as_ref(),
+            mint_sell.key().as_ref(),
+            mint_buy.key().as_ref(),
+            auction.auction_id.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub auction: Account<'info, state::Auction>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_sell,
+        associated_token::authority = auction,
+    )]
+    pub auction_sell_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_sell,
+        associated_token::authority = payer,
+    )]
+    pub seller_sell_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Buy closes the auction, so an open auction has no buys yet
+pub fn update(
+    ctx: Context<Update>,
+    // New end_price, must be <= current end_price
+    end_price: Option<u64>,
+    // New end_time, must be >= current end_time
+    end_time: Option<u64>,
+    // Amount of sell token to add to the lot
+    top_up: Option<u64>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now = u64::try_from(clock.unix_timestamp).unwrap();
+
+    let auction = &mut ctx.accounts.auction;
+
+    // Check auction has not ended
+    require!(now < auction.end_time, error::Error::AuctionEnded);
+
+    // Lower end_price, start_price >= end_price still holds
+    if let Some(end_price) = end_price {
+        require!(end_price <= auction.end_price, error::Error::InvalidPrices);
+        auction.end_price = end_price;
+    }
+
+    // Extend end_time, end_time > start_time still holds
+    if let Some(end_time) = end_time {
+        require!(end_time >= auction.end_time, error::Error::InvalidEndTime);
+        auction.end_time = end_time;
+    }
+
+    // Top up sell token
+    if let Some(top_up) = top_up {
+        require!(top_up > 0, error::Error::InvalidSellAmount);
+
+        lib::transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.seller_sell_ata,
+            &ctx.accounts.auction_sell_ata,
+            &ctx.accounts.payer,
+            top_up,
+        )?;
+    }
+
+    ctx.accounts.auction_sell_ata.reload()?;
+
+    emit!(events::AuctionUpdated {
+        auction: auction.key(),
+        end_price: auction.end_price,
+        end_time: auction.end_time,
+        sell_amt: ctx.accounts.auction_sell_ata.amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
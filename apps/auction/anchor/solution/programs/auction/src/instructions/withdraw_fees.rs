@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::lib;
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [state::Config::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, state::Config>,
+
+    pub mint_buy: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_buy,
+        associated_token::authority = config,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Token account of mint_buy receiving the fees
+    #[account(
+        mut,
+        token::mint = mint_buy,
+    )]
+    pub dst: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    // Check admin matches config
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        error::Error::NotAdmin
+    );
+
+    // Send fees to dst
+    let seeds: &[&[u8]] =
+        &[state::Config::SEED_PREFIX, &[ctx.accounts.config.bump]];
+
+    lib::transfer_from_pda(
+        &ctx.accounts.token_program,
+        &ctx.accounts.fee_vault,
+        &ctx.accounts.dst,
+        &ctx.accounts.config,
+        amount,
+        seeds,
+    )?;
+
+    Ok(())
+}
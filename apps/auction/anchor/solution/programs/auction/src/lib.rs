@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -13,6 +14,7 @@ pub mod auction {
 
     pub fn init(
         ctx: Context<Init>,
+        auction_id: u64,
         start_price: u64,
         end_price: u64,
         start_time: u64,
@@ -21,6 +23,7 @@ pub mod auction {
     ) -> Result<()> {
         instructions::init(
             ctx,
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -39,4 +42,32 @@ pub mod auction {
         instructions::cancel(ctx)?;
         Ok(())
     }
+
+    pub fn update(
+        ctx: Context<Update>,
+        end_price: Option<u64>,
+        end_time: Option<u64>,
+        top_up: Option<u64>,
+    ) -> Result<()> {
+        instructions::update(ctx, end_price, end_time, top_up)?;
+        Ok(())
+    }
+
+    pub fn init_config(ctx: Context<InitConfig>, fee_bps: u16) -> Result<()> {
+        instructions::init_config(ctx, fee_bps)?;
+        Ok(())
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+        instructions::set_fee(ctx, fee_bps)?;
+        Ok(())
+    }
+
+    pub fn withdraw_fees(
+        ctx: Context<WithdrawFees>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_fees(ctx, amount)?;
+        Ok(())
+    }
 }
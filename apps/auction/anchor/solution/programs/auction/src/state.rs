@@ -3,9 +3,15 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct Auction {
+    // seller is stored first so that clients can filter auctions by seller
+    pub seller: Pubkey,
+    pub auction_id: u64,
     pub mint_sell: Pubkey,
     pub mint_buy: Pubkey,
-    // Price of 1 sell token, 1e6 = 1 buy token
+    pub decimals_sell: u8,
+    pub decimals_buy: u8,
+    // Price of 1 whole sell token (10^decimals_sell units)
+    // in buy token units
     pub start_price: u64,
     pub end_price: u64,
     pub start_time: u64,
@@ -15,3 +21,19 @@ pub struct Auction {
 impl Auction {
     pub const SEED_PREFIX: &'static [u8; 7] = b"auction";
 }
+
+// Global protocol config
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    // Can change the fee and withdraw accrued fees
+    pub admin: Pubkey,
+    // Protocol fee taken from buy token paid by buyers, 100 = 1%
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"config";
+    pub const MAX_FEE_BPS: u16 = 1000;
+}
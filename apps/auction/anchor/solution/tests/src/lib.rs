@@ -3,7 +3,11 @@ mod test_buy;
 #[cfg(test)]
 mod test_cancel;
 #[cfg(test)]
+mod test_fee;
+#[cfg(test)]
 mod test_init;
+#[cfg(test)]
+mod test_update;
 
 mod test_helper;
 mod token_helper;
@@ -20,6 +20,7 @@ fn test_buy() {
         seller,
         buyer,
         auction_pda,
+        auction_id,
         auction_bump,
         mint_sell,
         mint_buy,
@@ -58,6 +59,7 @@ fn test_buy() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -68,6 +70,7 @@ fn test_buy() {
         .unwrap();
 
     // Buy
+    let (config_pda, _) = test_helper::get_config_pda();
     let wait_time = start_time - now + 2;
     println!("Waiting {:?} seconds for auction to start", wait_time);
     std::thread::sleep(std::time::Duration::from_secs(wait_time));
@@ -84,6 +87,11 @@ fn test_buy() {
             buyer_buy_ata,
             buyer_sell_ata,
             seller_buy_ata,
+            config: config_pda,
+            fee_vault: get_associated_token_address(
+                &config_pda,
+                &mint_buy.pubkey(),
+            ),
             token_program: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
@@ -20,6 +20,7 @@ fn test_cancel() {
         seller,
         buyer,
         auction_pda,
+        auction_id,
         auction_bump,
         mint_sell,
         mint_buy,
@@ -58,6 +59,7 @@ fn test_cancel() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -111,3 +113,114 @@ fn test_cancel() {
         "Auction sell ATA not closed"
     );
 }
+
+#[test]
+fn test_cancel_concurrent_auctions() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        buyer_sell_ata,
+        seller_buy_ata,
+        buyer_buy_ata,
+    } = test_helper::set_up(&payer);
+
+    // Init 2 auctions of the same seller and mints
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 50 * (1e6 as u64);
+
+    let auction_id_1 = auction_id + 1;
+    let (auction_pda_1, _) = test_helper::get_auction_pda(
+        &seller.pubkey(),
+        &mint_sell.pubkey(),
+        &mint_buy.pubkey(),
+        auction_id_1,
+    );
+
+    for (id, pda) in [(auction_id, auction_pda), (auction_id_1, auction_pda_1)]
+    {
+        program
+            .request()
+            .accounts(auction::accounts::Init {
+                payer: seller.pubkey(),
+                mint_sell: mint_sell.pubkey(),
+                mint_buy: mint_buy.pubkey(),
+                auction: pda,
+                auction_sell_ata: get_associated_token_address(
+                    &pda,
+                    &mint_sell.pubkey(),
+                ),
+                seller_sell_ata,
+                seller_buy_ata,
+                token_program: token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            })
+            .signer(&seller)
+            .args(auction::instruction::Init {
+                auction_id: id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+            })
+            .send()
+            .unwrap();
+    }
+
+    let auctions = test_helper::list_auctions(&program, &seller.pubkey());
+    assert_eq!(auctions.len(), 2, "Open auctions");
+    assert_eq!(auctions[0].0, auction_pda);
+    assert_eq!(auctions[1].0, auction_pda_1);
+    assert_eq!(auctions[1].1.auction_id, auction_id_1);
+
+    // Cancel second auction
+    let auction_sell_ata_1 =
+        get_associated_token_address(&auction_pda_1, &mint_sell.pubkey());
+
+    program
+        .request()
+        .accounts(auction::accounts::Cancel {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            auction: auction_pda_1,
+            auction_sell_ata: auction_sell_ata_1,
+            seller_sell_ata,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Cancel {})
+        .send()
+        .unwrap();
+
+    let auctions = test_helper::list_auctions(&program, &seller.pubkey());
+    assert_eq!(auctions.len(), 1, "Open auctions");
+    assert_eq!(auctions[0].0, auction_pda);
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_sell_ata).unwrap(),
+        sell_amt,
+        "Seller sell ATA balance"
+    );
+}
@@ -0,0 +1,222 @@
+use anchor_client::solana_sdk::signature::Signer;
+use anchor_client::solana_sdk::{signature::read_keypair_file, system_program};
+use anchor_spl::associated_token::{
+    get_associated_token_address, spl_associated_token_account,
+};
+use anchor_spl::token::{self};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::test_helper;
+use super::token_helper;
+
+#[test]
+fn test_fee() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        auction_pda,
+        auction_id,
+        auction_bump: _,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        buyer_sell_ata,
+        seller_buy_ata,
+        buyer_buy_ata,
+    } = test_helper::set_up(&payer);
+
+    let (config_pda, _) = test_helper::get_config_pda();
+    let fee_vault =
+        get_associated_token_address(&config_pda, &mint_buy.pubkey());
+
+    // Set fee by non admin
+    let fee_bps = 100;
+    let res = program
+        .request()
+        .accounts(auction::accounts::SetFee {
+            admin: seller.pubkey(),
+            config: config_pda,
+        })
+        .signer(&seller)
+        .args(auction::instruction::SetFee { fee_bps })
+        .send();
+    assert!(res.is_err(), "Non admin set fee");
+
+    // Fee > max
+    let res = program
+        .request()
+        .accounts(auction::accounts::SetFee {
+            admin: payer.pubkey(),
+            config: config_pda,
+        })
+        .args(auction::instruction::SetFee {
+            fee_bps: auction::state::Config::MAX_FEE_BPS + 1,
+        })
+        .send();
+    assert!(res.is_err(), "Fee > max");
+
+    // Set fee
+    program
+        .request()
+        .accounts(auction::accounts::SetFee {
+            admin: payer.pubkey(),
+            config: config_pda,
+        })
+        .args(auction::instruction::SetFee { fee_bps })
+        .send()
+        .unwrap();
+
+    // Init
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 100 * (1e6 as u64);
+    let auction_sell_ata =
+        get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+
+    program
+        .request()
+        .accounts(auction::accounts::Init {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            auction: auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Init {
+            auction_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+        })
+        .send()
+        .unwrap();
+
+    // Buy
+    let wait_time = start_time - now + 2;
+    println!("Waiting {:?} seconds for auction to start", wait_time);
+    std::thread::sleep(std::time::Duration::from_secs(wait_time));
+
+    let buyer_buy_bal =
+        token_helper::get_balance(&token_program, &buyer_buy_ata).unwrap();
+
+    program
+        .request()
+        .accounts(auction::accounts::Buy {
+            buyer: buyer.pubkey(),
+            seller: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            auction: auction_pda,
+            auction_sell_ata,
+            buyer_buy_ata,
+            buyer_sell_ata,
+            seller_buy_ata,
+            config: config_pda,
+            fee_vault,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&buyer)
+        .args(auction::instruction::Buy {
+            max_price: start_price,
+        })
+        .send()
+        .unwrap();
+
+    // Buyer pays buy_amt, fee_vault gets fee and seller the rest
+    let buy_amt = buyer_buy_bal
+        - token_helper::get_balance(&token_program, &buyer_buy_ata).unwrap();
+    let fee = buy_amt * fee_bps as u64 / 10000;
+    assert!(fee > 0, "Fee");
+    assert_eq!(
+        token_helper::get_balance(&token_program, &fee_vault).unwrap(),
+        fee,
+        "Fee vault balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_buy_ata).unwrap(),
+        buy_amt - fee,
+        "Seller buy ATA balance"
+    );
+
+    // Withdraw fees by non admin
+    let res = program
+        .request()
+        .accounts(auction::accounts::WithdrawFees {
+            admin: seller.pubkey(),
+            config: config_pda,
+            mint_buy: mint_buy.pubkey(),
+            fee_vault,
+            dst: seller_buy_ata,
+            token_program: token::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::WithdrawFees { amount: fee })
+        .send();
+    assert!(res.is_err(), "Non admin withdraw fees");
+
+    // Withdraw fees
+    let payer_buy_ata = token_helper::create_ata(
+        &token_program,
+        &payer,
+        &mint_buy.pubkey(),
+        &payer.pubkey(),
+    )
+    .unwrap();
+
+    program
+        .request()
+        .accounts(auction::accounts::WithdrawFees {
+            admin: payer.pubkey(),
+            config: config_pda,
+            mint_buy: mint_buy.pubkey(),
+            fee_vault,
+            dst: payer_buy_ata,
+            token_program: token::ID,
+        })
+        .args(auction::instruction::WithdrawFees { amount: fee })
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &fee_vault).unwrap(),
+        0,
+        "Fee vault balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &payer_buy_ata).unwrap(),
+        fee,
+        "Payer buy ATA balance"
+    );
+
+    // Reset fee for other tests
+    program
+        .request()
+        .accounts(auction::accounts::SetFee {
+            admin: payer.pubkey(),
+            config: config_pda,
+        })
+        .args(auction::instruction::SetFee { fee_bps: 0 })
+        .send()
+        .unwrap();
+}
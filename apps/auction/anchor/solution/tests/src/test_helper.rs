@@ -1,8 +1,9 @@
 use anchor_client::solana_sdk::signature::Signer;
 use anchor_client::{
+    solana_client::rpc_filter::{Memcmp, RpcFilterType},
     solana_sdk::{
-        commitment_config::CommitmentConfig, pubkey::Pubkey,
-        signature::Keypair, system_program,
+        bpf_loader_upgradeable, commitment_config::CommitmentConfig,
+        pubkey::Pubkey, signature::Keypair, system_program,
     },
     Client, Cluster, Program,
 };
@@ -16,6 +17,7 @@ pub struct Test<'a> {
     pub seller: Keypair,
     pub buyer: Keypair,
     pub auction_pda: Pubkey,
+    pub auction_id: u64,
     pub auction_bump: u8,
     pub mint_sell: Keypair,
     pub mint_buy: Keypair,
@@ -47,6 +49,27 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
     rpc.request_airdrop(&buyer.pubkey(), 100 * (1e9 as u64))
         .unwrap();
 
+    // Init config once, payer is upgrade authority and admin, no protocol
+    // fee
+    let (config_pda, _) = get_config_pda();
+    if program
+        .account::<auction::state::Config>(config_pda)
+        .is_err()
+    {
+        // Tests run in parallel, another test may have created it
+        let _ = program
+            .request()
+            .accounts(auction::accounts::InitConfig {
+                payer: payer.pubkey(),
+                config: config_pda,
+                program: program_id,
+                program_data: get_program_data(),
+                system_program: system_program::ID,
+            })
+            .args(auction::instruction::InitConfig { fee_bps: 0 })
+            .send();
+    }
+
     // Mint sell and buy tokens
     let token_program = client.program(token::ID).unwrap();
     let mint_sell = Keypair::new();
@@ -107,14 +130,12 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
     .unwrap();
 
     // Calculate Auction PDA
-    let (pda, bump) = Pubkey::find_program_address(
-        &[
-            auction::state::Auction::SEED_PREFIX,
-            &seller.pubkey().as_ref(),
-            mint_sell.pubkey().as_ref(),
-            mint_buy.pubkey().as_ref(),
-        ],
-        &program_id,
+    let auction_id = 0;
+    let (pda, bump) = get_auction_pda(
+        &seller.pubkey(),
+        &mint_sell.pubkey(),
+        &mint_buy.pubkey(),
+        auction_id,
     );
 
     Test {
@@ -123,6 +144,7 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
         seller,
         buyer,
         auction_pda: pda,
+        auction_id,
         auction_bump: bump,
         mint_sell,
         mint_buy,
@@ -132,3 +154,54 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
         buyer_buy_ata,
     }
 }
+
+pub fn get_auction_pda(
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    auction_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            auction::state::Auction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &auction_id.to_le_bytes(),
+        ],
+        &auction::ID,
+    )
+}
+
+pub fn get_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[auction::state::Config::SEED_PREFIX],
+        &auction::ID,
+    )
+}
+
+pub fn get_program_data() -> Pubkey {
+    let (program_data, _) = Pubkey::find_program_address(
+        &[auction::ID.as_ref()],
+        &bpf_loader_upgradeable::ID,
+    );
+    program_data
+}
+
+// List open auctions of seller
+// Closed auctions are deleted, so every auction account found is open
+pub fn list_auctions(
+    program: &Program<&Keypair>,
+    seller: &Pubkey,
+) -> Vec<(Pubkey, auction::state::Auction)> {
+    // Auction.seller is stored right after the 8 byte discriminator
+    let mut auctions = program
+        .accounts::<auction::state::Auction>(vec![RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(8, seller.as_ref()),
+        )])
+        .unwrap();
+
+    auctions.sort_by_key(|(_, auction)| auction.auction_id);
+
+    auctions
+}
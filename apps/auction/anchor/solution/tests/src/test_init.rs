@@ -20,6 +20,7 @@ fn test_init() {
         seller,
         buyer,
         auction_pda,
+        auction_id,
         auction_bump,
         mint_sell,
         mint_buy,
@@ -62,6 +63,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -89,6 +91,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price: start_price + 1,
             start_time,
@@ -116,6 +119,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time: now - 1,
@@ -143,6 +147,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -170,6 +175,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -198,6 +204,7 @@ fn test_init() {
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            auction_id,
             start_price,
             end_price,
             start_time,
@@ -209,8 +216,12 @@ fn test_init() {
 
     let auction: auction::state::Auction =
         program.account(auction_pda).unwrap();
+    assert_eq!(auction.seller, seller.pubkey(), "auction.seller");
+    assert_eq!(auction.auction_id, auction_id, "auction.auction_id");
     assert_eq!(auction.mint_sell, mint_sell.pubkey(), "auction.mint_sell");
     assert_eq!(auction.mint_buy, mint_buy.pubkey(), "auction.mint_buy");
+    assert_eq!(auction.decimals_sell, 6, "auction.decimals_sell");
+    assert_eq!(auction.decimals_buy, 6, "auction.decimals_buy");
     assert_eq!(auction.start_time, start_time, "auction.start_time");
     assert_eq!(auction.end_time, end_time, "auction.end_time");
     assert_eq!(auction.end_time, end_time, "auction.end_time");
@@ -0,0 +1,124 @@
+use anchor_client::solana_sdk::signature::Signer;
+use anchor_client::solana_sdk::{signature::read_keypair_file, system_program};
+use anchor_spl::associated_token::{
+    get_associated_token_address, spl_associated_token_account,
+};
+use anchor_spl::token::{self};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::test_helper;
+use super::token_helper;
+
+#[test]
+fn test_update() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        buyer_sell_ata,
+        seller_buy_ata,
+        buyer_buy_ata,
+    } = test_helper::set_up(&payer);
+
+    // Init
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 60 * (1e6 as u64);
+    let auction_sell_ata =
+        get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+
+    program
+        .request()
+        .accounts(auction::accounts::Init {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            auction: auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Init {
+            auction_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+        })
+        .send()
+        .unwrap();
+
+    let update =
+        |end_price: Option<u64>, end_time: Option<u64>, top_up: Option<u64>| {
+            program
+                .request()
+                .accounts(auction::accounts::Update {
+                    payer: seller.pubkey(),
+                    mint_sell: mint_sell.pubkey(),
+                    mint_buy: mint_buy.pubkey(),
+                    auction: auction_pda,
+                    auction_sell_ata,
+                    seller_sell_ata,
+                    token_program: token::ID,
+                })
+                .signer(&seller)
+                .args(auction::instruction::Update {
+                    end_price,
+                    end_time,
+                    top_up,
+                })
+                .send()
+        };
+
+    // Raise end_price
+    assert!(
+        update(Some(end_price + 1), None, None).is_err(),
+        "Raise end_price"
+    );
+    // Shorten end_time
+    assert!(
+        update(None, Some(end_time - 1), None).is_err(),
+        "Shorten end_time"
+    );
+    // Top up 0
+    assert!(update(None, None, Some(0)).is_err(), "Top up 0");
+
+    // Lower end_price, extend end_time and top up
+    let new_end_price = (1.0 * 1e6) as u64;
+    let new_end_time = end_time + 20;
+    let top_up = 40 * (1e6 as u64);
+    update(Some(new_end_price), Some(new_end_time), Some(top_up)).unwrap();
+
+    let auction: auction::state::Auction =
+        program.account(auction_pda).unwrap();
+    assert_eq!(auction.start_price, start_price, "auction.start_price");
+    assert_eq!(auction.end_price, new_end_price, "auction.end_price");
+    assert_eq!(auction.end_time, new_end_time, "auction.end_time");
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &auction_sell_ata).unwrap(),
+        sell_amt + top_up,
+        "Auction sell ATA balance"
+    );
+}
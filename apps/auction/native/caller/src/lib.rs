@@ -0,0 +1,119 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    declare_id,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use auction::instructions::read_quote;
+
+// Example of a program reading the price of an auction through CPI
+declare_id!("9peFWYybaBwLgmMziCQS5CEZtuzkmpNf88AxHABGjht1");
+
+// Auction program the quote is read from. Return data is set by the callee,
+// so a quote is only trusted from this program.
+pub const AUCTION_PROGRAM_ID: Pubkey =
+    pubkey!("BMHqzLECSvaKobAE1q6bAxDNEjTAF5yQ5cp7HMWGH7S3");
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum Cmd {
+    // Quote of amount of sell token from GetPrice of the auction program,
+    // fails if the price > max_price. Returned as instructions::Quote.
+    GetQuote {
+        amount: u64,
+        max_price: u64,
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let ix = Cmd::try_from_slice(instruction_data)?;
+
+    match ix {
+        Cmd::GetQuote {
+            amount,
+            max_price,
+            auction_id,
+            bump,
+        } => {
+            get_quote(accounts, amount, max_price, auction_id, bump)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn get_quote(
+    accounts: &[AccountInfo],
+    amount: u64,
+    max_price: u64,
+    auction_id: u64,
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let auction_program = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+
+    // Check auction_program is the auction program.
+    // Any program can set return data in the layout of Quote.
+    if *auction_program.key != AUCTION_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Auction program checks auction_pda
+    let ix = Instruction::new_with_borsh(
+        AUCTION_PROGRAM_ID,
+        &auction::Cmd::GetPrice {
+            amount,
+            auction_id,
+            bump,
+        },
+        vec![
+            AccountMeta::new_readonly(*seller.key, false),
+            AccountMeta::new_readonly(*mint_sell.key, false),
+            AccountMeta::new_readonly(*mint_buy.key, false),
+            AccountMeta::new_readonly(*auction_pda.key, false),
+        ],
+    );
+    invoke(
+        &ix,
+        &[
+            seller.clone(),
+            mint_sell.clone(),
+            mint_buy.clone(),
+            auction_pda.clone(),
+            auction_program.clone(),
+        ],
+    )?;
+
+    // Fails unless return data was set by the auction program
+    let quote = read_quote(&AUCTION_PROGRAM_ID)?;
+
+    // Check price <= max_price
+    if quote.price > max_price {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    msg!("Price: {}, buy amount: {}", quote.price, quote.buy_amt);
+    set_return_data(&borsh::to_vec(&quote)?);
+
+    Ok(())
+}
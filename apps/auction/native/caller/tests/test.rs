@@ -0,0 +1,208 @@
+use borsh::BorshDeserialize;
+use litesvm::{LiteSVM, types::TransactionResult};
+use solana_program::sysvar::clock::Clock;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use auction::{
+    instructions::Quote,
+    state::{Auction, AuctionKind, PriceCurve},
+};
+use caller::{AUCTION_PROGRAM_ID, Cmd};
+
+struct Test {
+    payer: Keypair,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_id: u64,
+}
+
+fn setup(svm: &mut LiteSVM) -> Test {
+    svm.add_program_from_file(caller::ID, "target/deploy/caller.so")
+        .unwrap();
+    svm.add_program_from_file(
+        AUCTION_PROGRAM_ID,
+        "../solution/target/deploy/auction.so",
+    )
+    .unwrap();
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    Test {
+        payer,
+        seller: Pubkey::new_unique(),
+        mint_sell: Pubkey::new_unique(),
+        mint_buy: Pubkey::new_unique(),
+        auction_id: 0,
+    }
+}
+
+fn set_time(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+fn get_auction_pda(test: &Test, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Auction::SEED_PREFIX,
+            test.seller.as_ref(),
+            test.mint_sell.as_ref(),
+            test.mint_buy.as_ref(),
+            &test.auction_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// Write a Dutch auction from $2 to $1 between 1000 and 1010 selling 100
+// tokens with 6 decimals, owned by the auction program unless spoofed
+fn set_auction(svm: &mut LiteSVM, test: &Test, owner: Pubkey) -> (Pubkey, u8) {
+    let mut auction =
+        Auction::try_from_slice(&[0; Auction::SPACE as usize]).unwrap();
+    auction.seller = test.seller;
+    auction.auction_id = test.auction_id;
+    auction.kind = AuctionKind::Dutch;
+    auction.curve = PriceCurve::Linear;
+    auction.mint_sell = test.mint_sell;
+    auction.mint_buy = test.mint_buy;
+    auction.decimals_sell = 6;
+    auction.decimals_buy = 6;
+    auction.start_price = 2_000_000;
+    auction.end_price = 1_000_000;
+    auction.start_time = 1000;
+    auction.end_time = 1010;
+    auction.sell_amt = 100_000_000;
+    auction.remaining = 100_000_000;
+
+    let (auction_pda, bump) = get_auction_pda(test, &owner);
+    svm.set_account(
+        auction_pda,
+        Account {
+            lamports: 10_000_000,
+            owner,
+            data: borsh::to_vec(&auction).unwrap(),
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    (auction_pda, bump)
+}
+
+fn get_quote(
+    svm: &mut LiteSVM,
+    test: &Test,
+    auction_program: Pubkey,
+    auction_pda: Pubkey,
+    bump: u8,
+    amount: u64,
+    max_price: u64,
+) -> TransactionResult {
+    let ix = Instruction::new_with_borsh(
+        caller::ID,
+        &Cmd::GetQuote {
+            amount,
+            max_price,
+            auction_id: test.auction_id,
+            bump,
+        },
+        vec![
+            AccountMeta::new_readonly(auction_program, false),
+            AccountMeta::new_readonly(test.seller, false),
+            AccountMeta::new_readonly(test.mint_sell, false),
+            AccountMeta::new_readonly(test.mint_buy, false),
+            AccountMeta::new_readonly(auction_pda, false),
+        ],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&test.payer.pubkey()),
+        &[&test.payer],
+        svm.latest_blockhash(),
+    ));
+    svm.expire_blockhash();
+    res
+}
+
+// Check that transaction failed with err
+fn assert_error(res: TransactionResult, err: InstructionError) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => assert_eq!(e, err),
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn test() {
+    let mut svm = LiteSVM::new();
+    let test = setup(&mut svm);
+
+    let (auction_pda, bump) = set_auction(&mut svm, &test, AUCTION_PROGRAM_ID);
+
+    // Halfway, price dropped to $1.5
+    set_time(&mut svm, 1005);
+
+    // 2 tokens
+    let res = get_quote(
+        &mut svm,
+        &test,
+        AUCTION_PROGRAM_ID,
+        auction_pda,
+        bump,
+        2_000_000,
+        1_500_000,
+    )
+    .unwrap();
+    assert_eq!(res.return_data.program_id, caller::ID);
+    let quote = Quote::try_from_slice(&res.return_data.data).unwrap();
+    assert_eq!(quote.price, 1_500_000);
+    assert_eq!(quote.buy_amt, 3_000_000);
+
+    // Price > max_price
+    assert_error(
+        get_quote(
+            &mut svm,
+            &test,
+            AUCTION_PROGRAM_ID,
+            auction_pda,
+            bump,
+            2_000_000,
+            1_499_999,
+        ),
+        InstructionError::InvalidArgument,
+    );
+}
+
+#[test]
+fn test_spoof() {
+    let mut svm = LiteSVM::new();
+    let test = setup(&mut svm);
+
+    set_time(&mut svm, 1005);
+
+    // Another program returning a quote, here the caller itself
+    let (auction_pda, bump) = set_auction(&mut svm, &test, caller::ID);
+
+    assert_error(
+        get_quote(
+            &mut svm,
+            &test,
+            caller::ID,
+            auction_pda,
+            bump,
+            2_000_000,
+            u64::MAX,
+        ),
+        InstructionError::IncorrectProgramId,
+    );
+}
@@ -15,7 +15,8 @@ use std::thread;
 use std::time::Duration;
 
 use auction::Cmd;
-use auction::state::Auction;
+use auction::instructions::InitParams;
+use auction::state::{Auction, AuctionKind, Config, PriceCurve};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -55,18 +56,63 @@ fn main() {
     mint_to(&client, &payer, &mint_buy, &buyer_buy_ata, 1e9 as u64);
 
     // Derive PDAs
+    let auction_id: u64 = 0;
     let (auction_pda, bump) = Pubkey::find_program_address(
         &[
             Auction::SEED_PREFIX,
             seller.pubkey().as_ref(),
             mint_sell.as_ref(),
             mint_buy.as_ref(),
+            &auction_id.to_le_bytes(),
         ],
         &program_id,
     );
 
     let auction_sell_ata = get_ata(&auction_pda, &mint_sell);
 
+    let (config_pda, config_bump) =
+        Pubkey::find_program_address(&[Config::SEED_PREFIX], &program_id);
+    let fee_vault = get_ata(&config_pda, &mint_buy);
+
+    // Init config, once per program
+    if client.get_account(&config_pda).is_err() {
+        println!("Init config");
+
+        let cmd = Cmd::InitConfig {
+            fee_bps: 0,
+            bump: config_bump,
+        };
+
+        let ix = Instruction::new_with_borsh(
+            program_id,
+            &cmd,
+            vec![
+                AccountMeta {
+                    pubkey: payer.pubkey(),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: config_pda,
+                    is_signer: false,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: solana_sdk::system_program::id(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+        );
+
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+        let blockhash = client.get_latest_blockhash().unwrap();
+        tx.sign(&[&payer], blockhash);
+
+        let res = client.send_and_confirm_transaction(&tx);
+        res.unwrap();
+    }
+
     // Init
     println!("Init");
 
@@ -84,6 +130,14 @@ fn main() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         bump,
         seller.pubkey(),
         mint_sell,
@@ -110,7 +164,10 @@ fn main() {
     thread::sleep(Duration::from_millis(1500));
 
     let cmd = Cmd::Buy {
+        amount: sell_amt,
         max_price: start_price - 1,
+        allowlist: None,
+        auction_id,
         bump,
     };
 
@@ -163,16 +220,39 @@ fn main() {
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: fee_vault,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     );
 
@@ -212,6 +292,14 @@ fn main() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         bump,
         seller.pubkey(),
         mint_sell,
@@ -230,7 +318,7 @@ fn main() {
 
     // Cancel
     println!("Cancel");
-    let cmd = Cmd::Cancel { bump };
+    let cmd = Cmd::Cancel { auction_id, bump };
 
     let ix = Instruction::new_with_borsh(
         program_id,
@@ -305,6 +393,14 @@ fn create_init_ix(
     start_time: u64,
     end_time: u64,
     sell_amt: u64,
+    kind: AuctionKind,
+    curve: PriceCurve,
+    curve_period: u64,
+    allowlist_root: Option<[u8; 32]>,
+    reveal_period: u64,
+    nft: bool,
+    collection: Option<Pubkey>,
+    auction_id: u64,
     bump: u8,
     seller: Pubkey,
     mint_sell: Pubkey,
@@ -313,14 +409,22 @@ fn create_init_ix(
     auction_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Init {
+    let cmd = Cmd::Init(InitParams {
         start_price,
         end_price,
         start_time,
         end_time,
         sell_amt,
+        kind,
+        curve,
+        curve_period,
+        allowlist_root,
+        reveal_period,
+        nft,
+        collection,
+        auction_id,
         bump,
-    };
+    });
 
     Instruction::new_with_borsh(
         program_id,
@@ -0,0 +1,30 @@
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+
+use auction::client::list_auctions;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let rpc_url = String::from(&args[1]);
+    let client =
+        RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    let program_id = Pubkey::from_str(&args[2]).expect("Invalid program ID");
+    let seller = Pubkey::from_str(&args[3]).expect("Invalid seller");
+
+    let auctions = list_auctions(&client, &program_id, &seller)
+        .expect("Failed to list auctions");
+
+    for (pubkey, auction) in auctions {
+        println!(
+            "{} auction_id: {} mint_sell: {} mint_buy: {} remaining: {}",
+            pubkey,
+            auction.auction_id,
+            auction.mint_sell,
+            auction.mint_buy,
+            auction.remaining
+        );
+    }
+}
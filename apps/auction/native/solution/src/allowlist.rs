@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+// Buyer can buy any amount
+pub const NO_CAP: u64 = u64::MAX;
+
+// Domain separation so that a leaf can never be used as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AllowlistProof {
+    // Max amount of sell token the buyer can buy, NO_CAP if unlimited
+    pub cap: u64,
+    // Sibling hashes from leaf to root
+    pub proof: Vec<[u8; 32]>,
+    // Allocation PDA bump, ignored if cap = NO_CAP
+    pub bump: u8,
+}
+
+pub fn get_leaf(buyer: &Pubkey, cap: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, buyer.as_ref(), &cap.to_le_bytes()]).to_bytes()
+}
+
+// Pairs are sorted, so proofs don't need left / right flags
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, lo, hi]).to_bytes()
+}
+
+pub fn verify(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    let node = proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_pair(&node, sibling));
+
+    node == *root
+}
+
+// Off-chain helpers to build the allowlist
+
+// Levels of the tree, leaves first and root last
+// A node without a sibling is carried up to the next level
+pub fn build_tree(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+    assert!(!leaves.is_empty(), "no leaves");
+
+    let mut tree = vec![leaves.to_vec()];
+
+    while tree[tree.len() - 1].len() > 1 {
+        let level = tree[tree.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        tree.push(level);
+    }
+
+    tree
+}
+
+pub fn get_root(tree: &[Vec<[u8; 32]>]) -> [u8; 32] {
+    tree[tree.len() - 1][0]
+}
+
+// Proof for the leaf at index
+pub fn get_proof(tree: &[Vec<[u8; 32]>], index: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    let mut i = index;
+
+    for level in &tree[..tree.len() - 1] {
+        let sibling = i ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        i /= 2;
+    }
+
+    proof
+}
@@ -0,0 +1,51 @@
+use borsh::BorshDeserialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    client_error::ClientError,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::pubkey::Pubkey;
+
+use crate::state::Auction;
+
+// Off-chain RPC helpers, enabled by the client feature
+
+// List open auctions of seller, sorted by auction_id
+// Closed auctions are deleted, so every auction account found is open
+pub fn list_auctions(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    seller: &Pubkey,
+) -> Result<Vec<(Pubkey, Auction)>, ClientError> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Auction::SPACE),
+            // Auction.seller is stored at offset 0
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                seller.as_ref(),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let mut auctions: Vec<(Pubkey, Auction)> = client
+        .get_program_accounts_with_config(program_id, config)?
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            Auction::try_from_slice(&account.data)
+                .ok()
+                .map(|auction| (pubkey, auction))
+        })
+        .collect();
+
+    auctions.sort_by_key(|(_, auction)| auction.auction_id);
+
+    Ok(auctions)
+}
@@ -0,0 +1,70 @@
+use solana_program::program_error::ProgramError;
+
+// Returned as ProgramError::Custom(code).
+// Codes of errors shared with the Anchor program match its error codes,
+// which start at 6000.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AuctionError {
+    // Sell mint = buy mint
+    InvalidMints = 6000,
+    // Start price < end price
+    InvalidPrices,
+    InvalidStartTime,
+    InvalidEndTime,
+    // Sell amount must be > 0
+    InvalidSellAmount,
+    AuctionNotStarted,
+    AuctionEnded,
+    // Current price < end price
+    InvalidCurrentPrice,
+    // Current price > max price
+    MaxPrice,
+    MathOverflow,
+    // Fee > max fee
+    InvalidFee,
+    NotAdmin,
+    // Native only
+    // Token account is not the expected ATA
+    InvalidTokenAccount,
+    // Instruction is not supported by this kind of auction
+    InvalidAuctionKind,
+    // Curve period does not match the price curve
+    InvalidCurvePeriod,
+    // Reveal period must be > 0 only for Sealed auction
+    InvalidRevealPeriod,
+    // Mint is not an NFT or auction can't sell an NFT
+    InvalidNft,
+    // NFT is not a verified member of the collection
+    InvalidCollection,
+    // Allowlist root = [0; 32] or allowlist proof missing
+    InvalidAllowlist,
+    // Buyer is not in the allowlist
+    NotAllowed,
+    // Amount bought > allowlist cap
+    AllocationExceeded,
+    // Amount must be > 0 and <= remaining
+    InvalidAmount,
+    // Amount of buy token rounds to 0
+    InvalidBuyAmount,
+    // Creator does not match the NFT metadata
+    InvalidCreator,
+    // Auction has bids, bidders' escrow would be locked, or buys
+    HasBids,
+    // Auction, or reveal phase of Sealed auction, has not ended
+    AuctionNotEnded,
+    // Bid price < end price or <= highest bid
+    BidTooLow,
+    // Account is not the highest bidder of English auction
+    NotHighestBidder,
+    // Batch bid is not at its position in the bids sorted by price
+    InvalidBidOrder,
+    // Batch auction is not cleared yet
+    NotCleared,
+}
+
+impl From<AuctionError> for ProgramError {
+    fn from(e: AuctionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
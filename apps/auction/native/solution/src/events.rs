@@ -0,0 +1,91 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    log::sol_log_data, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::state::{AuctionKind, PriceCurve};
+
+// Events are logged with sol_log_data as Borsh serialized AuctionEvent,
+// which shows up in transaction logs as "Program data: <base64>".
+// See history for parsing them back.
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub auction_id: u64,
+    pub kind: AuctionKind,
+    pub curve: PriceCurve,
+    pub curve_period: u64,
+    // [0; 32] if anyone can buy
+    pub allowlist_root: [u8; 32],
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub sell_amt: u64,
+    pub reveal_period: u64,
+    pub nft: bool,
+    // Pubkey::default() if not checked
+    pub collection: Pubkey,
+}
+
+// Parameters changed by update, all values are after the update
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuctionUpdated {
+    pub auction: Pubkey,
+    pub end_price: u64,
+    pub end_time: u64,
+    pub sell_amt: u64,
+    pub timestamp: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuctionFilled {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    // Amount of sell token bought
+    pub amount: u64,
+    // Price of 1 sell token
+    pub price: u64,
+    // Amount of buy token paid, including fee and royalties
+    pub buy_amt: u64,
+    // Amount of sell token left
+    pub remaining: u64,
+    pub timestamp: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    // Amount of sell token returned to seller
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+// Auction PDA closed by buy of the remaining amount, settle, batch_settle,
+// sealed_settle or reclaim. Cancel emits AuctionCancelled instead.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuctionClosed {
+    pub auction: Pubkey,
+    // Amount of sell token returned to seller
+    pub unsold: u64,
+    pub timestamp: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AuctionEvent {
+    Created(AuctionCreated),
+    Filled(AuctionFilled),
+    Cancelled(AuctionCancelled),
+    Closed(AuctionClosed),
+    Updated(AuctionUpdated),
+}
+
+pub fn emit(event: &AuctionEvent) -> Result<(), ProgramError> {
+    sol_log_data(&[&borsh::to_vec(event)?]);
+    Ok(())
+}
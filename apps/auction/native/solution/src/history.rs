@@ -0,0 +1,131 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+
+use crate::events::{
+    AuctionCancelled, AuctionClosed, AuctionCreated, AuctionEvent,
+    AuctionFilled, AuctionUpdated,
+};
+
+// Off-chain helpers to rebuild auction history from transaction logs
+
+const DATA_PREFIX: &str = "Program data: ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuctionHistory {
+    pub created: AuctionCreated,
+    // Updates by the seller, created keeps the parameters at init
+    pub updates: Vec<AuctionUpdated>,
+    pub fills: Vec<AuctionFilled>,
+    pub cancelled: Option<AuctionCancelled>,
+    pub closed: Option<AuctionClosed>,
+}
+
+impl AuctionHistory {
+    // Cancelled, sold out, settled or reclaimed, the auction PDA is closed
+    pub fn is_closed(&self) -> bool {
+        self.cancelled.is_some() || self.closed.is_some()
+    }
+
+    // Total amount of sell token sold and buy token paid
+    pub fn get_totals(&self) -> (u64, u64) {
+        self.fills.iter().fold((0, 0), |(sold, paid), fill| {
+            (
+                sold.saturating_add(fill.amount),
+                paid.saturating_add(fill.buy_amt),
+            )
+        })
+    }
+}
+
+// Events emitted by program_id in the logs of a transaction, in order.
+// Events of other programs are skipped, including events logged by
+// programs called through CPI from the auction program.
+pub fn parse_events(program_id: &Pubkey, logs: &[String]) -> Vec<AuctionEvent> {
+    let program_id = program_id.to_string();
+    // Programs currently executing, last is the one logging
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix(DATA_PREFIX) {
+            if stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            // Event is the first field of sol_log_data
+            let Some(data) = data.split(' ').next() else {
+                continue;
+            };
+            let Ok(bytes) = STANDARD.decode(data) else {
+                continue;
+            };
+            if let Ok(event) = AuctionEvent::try_from_slice(&bytes) {
+                events.push(event);
+            }
+        } else if let Some((program, status)) = log
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+        {
+            // "Program <id> invoke [<depth>]", "Program <id> success" or
+            // "Program <id> failed: <error>"
+            if status.starts_with("invoke [") {
+                stack.push(program);
+            } else if status == "success" || status.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+
+    events
+}
+
+// Rebuild history of every auction created in txs.
+// txs are the logs of each transaction, oldest first. An auction PDA can be
+// created again after it is closed, each creation is a separate entry.
+pub fn get_history(
+    program_id: &Pubkey,
+    txs: &[Vec<String>],
+) -> Vec<AuctionHistory> {
+    let mut history: Vec<AuctionHistory> = Vec::new();
+
+    for logs in txs {
+        for event in parse_events(program_id, logs) {
+            let auction = match &event {
+                AuctionEvent::Created(created) => {
+                    history.push(AuctionHistory {
+                        created: created.clone(),
+                        updates: Vec::new(),
+                        fills: Vec::new(),
+                        cancelled: None,
+                        closed: None,
+                    });
+                    continue;
+                }
+                AuctionEvent::Filled(filled) => filled.auction,
+                AuctionEvent::Cancelled(cancelled) => cancelled.auction,
+                AuctionEvent::Closed(closed) => closed.auction,
+                AuctionEvent::Updated(updated) => updated.auction,
+            };
+
+            // Latest open entry of the auction, skip events of auctions
+            // created before txs
+            let Some(entry) = history.iter_mut().rev().find(|entry| {
+                entry.created.auction == auction && !entry.is_closed()
+            }) else {
+                continue;
+            };
+
+            match event {
+                AuctionEvent::Filled(filled) => entry.fills.push(filled),
+                AuctionEvent::Cancelled(cancelled) => {
+                    entry.cancelled = Some(cancelled)
+                }
+                AuctionEvent::Closed(closed) => entry.closed = Some(closed),
+                AuctionEvent::Updated(updated) => entry.updates.push(updated),
+                AuctionEvent::Created(_) => {}
+            }
+        }
+    }
+
+    history
+}
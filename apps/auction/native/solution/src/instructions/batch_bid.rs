@@ -0,0 +1,224 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    create_ata, create_pda, get_ata, get_batch_bid_pda, get_buy_amt, get_pda,
+    transfer,
+};
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionKind, BatchBid};
+
+pub fn batch_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Amount of sell token to buy
+    quantity: u64,
+    // Max price of 1 sell token
+    price: u64,
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+    // Batch bid PDA bump
+    bid_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+    // Bid PDA to insert the new bid after, auction_pda to insert it first
+    let prev_pda = next_account_info(account_iter)?;
+    // Bid PDA after prev_pda, Pubkey::default() if prev_pda is the last bid
+    let next_pda = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_batch_bid_pda(program_id, auction_pda.key, bidder.key, bid_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key != get_ata(auction_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key != get_ata(bidder.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is a Batch auction
+    if auction.kind != AuctionKind::Batch {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check price >= end_price
+    if price < auction.end_price {
+        return Err(AuctionError::BidTooLow.into());
+    }
+    // Check 0 < quantity <= sell_amt
+    if quantity == 0 || quantity > auction.sell_amt {
+        return Err(AuctionError::InvalidAmount.into());
+    }
+
+    // Calculate amount of buy token to escrow
+    let buy_amt = get_buy_amt(quantity, price, auction.decimals_sell)?;
+    if buy_amt == 0 {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Bids are kept sorted by price, highest first, so that batch_clear can
+    // count them over several transactions.
+    // Check prev_pda.price >= price > next_pda.price
+    let mut prev = None;
+    let next = if *prev_pda.key == *auction_pda.key {
+        auction.bid_head
+    } else {
+        // Check prev_pda is a bid of this auction
+        if prev_pda.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let bid = {
+            let data = prev_pda.data.borrow();
+            BatchBid::try_from_slice(&data)?
+        }; // Drop borrow here
+        if *prev_pda.key
+            != get_batch_bid_pda(
+                program_id,
+                auction_pda.key,
+                &bid.bidder,
+                bid.bump,
+            )?
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if bid.price < price {
+            return Err(AuctionError::InvalidBidOrder.into());
+        }
+        let next = bid.next;
+        prev = Some(bid);
+        next
+    };
+    if *next_pda.key != next {
+        return Err(AuctionError::InvalidBidOrder.into());
+    }
+    if next != Pubkey::default() {
+        let data = next_pda.data.borrow();
+        if BatchBid::try_from_slice(&data)?.price >= price {
+            return Err(AuctionError::InvalidBidOrder.into());
+        }
+    }
+
+    // Create bid PDA, fails if bidder already bid
+    create_pda(
+        program_id,
+        bidder,
+        bid_pda,
+        sys_program,
+        BatchBid::SPACE,
+        &[
+            BatchBid::SEED_PREFIX,
+            auction_pda.key.as_ref(),
+            bidder.key.as_ref(),
+            &[bid_bump],
+        ],
+    )?;
+
+    // Create auction_buy_ata on first bid
+    if auction_buy_ata.data_is_empty() {
+        create_ata(
+            bidder,
+            mint_buy,
+            auction_pda,
+            auction_buy_ata,
+            token_program,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
+    // Send buy token to auction_buy_ata
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        auction_buy_ata,
+        bidder,
+        buy_amt,
+    )?;
+
+    // Store BatchBid state
+    {
+        let mut data = bid_pda.data.borrow_mut();
+        let bid = BatchBid {
+            bidder: *bidder.key,
+            quantity,
+            price,
+            next,
+            bump: bid_bump,
+        };
+        bid.serialize(&mut &mut data[..])?;
+    }
+
+    // Link bid after prev_pda
+    if let Some(mut prev) = prev {
+        prev.next = *bid_pda.key;
+        let mut data = prev_pda.data.borrow_mut();
+        prev.serialize(&mut &mut data[..])?;
+    } else {
+        auction.bid_head = *bid_pda.key;
+    }
+
+    // Store Auction state
+    auction.num_bids += 1;
+
+    let mut data = auction_pda.data.borrow_mut();
+    auction.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
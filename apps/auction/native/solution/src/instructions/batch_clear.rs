@@ -0,0 +1,125 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::get_pda;
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionKind, BatchBid};
+
+pub fn batch_clear(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can clear
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    // Rest of the accounts are bid PDAs of the auction in price order,
+    // starting after the last bid counted by the previous batch_clear
+    let bid_pdas = account_iter.as_slice();
+
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is a Batch auction
+    if auction.kind != AuctionKind::Batch {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has ended
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+    // Check auction has bids and is not cleared yet
+    // Auction without bids is closed by reclaim
+    if auction.num_bids == 0 || auction.clearing_price != 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Count bids from the highest price down, continuing from the last
+    // batch_clear. Bids are filled until supply runs out, clearing_price is
+    // the lowest filled price.
+    let mut next = if auction.clear_price == 0 {
+        auction.bid_head
+    } else {
+        auction.clear_next
+    };
+    // Cleared when every bid is counted or supply runs out before the next
+    // lower price
+    let mut cleared = false;
+
+    for bid_pda in bid_pdas {
+        // Check bid_pda is the next bid, bids are linked by the program
+        if *bid_pda.key != next || next == Pubkey::default() {
+            return Err(AuctionError::InvalidBidOrder.into());
+        }
+
+        let bid = {
+            let data = bid_pda.data.borrow();
+            BatchBid::try_from_slice(&data)?
+        }; // Drop borrow here
+
+        // All bids at clear_price are counted
+        if bid.price != auction.clear_price && auction.clear_price != 0 {
+            if auction.clear_above as u128 + auction.tie_demand
+                >= auction.sell_amt as u128
+            {
+                cleared = true;
+                break;
+            }
+            // clear_above + tie_demand < sell_amt
+            auction.clear_above += auction.tie_demand as u64;
+            auction.tie_demand = 0;
+        }
+
+        auction.clear_price = bid.price;
+        auction.tie_demand += bid.quantity as u128;
+        next = bid.next;
+    }
+    auction.clear_next = next;
+
+    if cleared || next == Pubkey::default() {
+        // Sell token left for bids at clear_price
+        let supply = auction.sell_amt - auction.clear_above;
+        auction.clearing_price = auction.clear_price;
+        auction.tie_supply = if auction.tie_demand < supply as u128 {
+            auction.tie_demand as u64
+        } else {
+            supply
+        };
+    }
+
+    // Store Auction state
+    let mut data = auction_pda.data.borrow_mut();
+    auction.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    close_ata, close_pda, create_ata, get_ata, get_batch_bid_pda,
+    get_batch_fill, get_buy_amt, get_fee, get_fee_bps, get_pda,
+    get_token_balance, sweep_and_close_ata, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionClosed, AuctionEvent, AuctionFilled};
+use crate::state::{Auction, AuctionKind, BatchBid};
+
+pub fn batch_settle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can settle, payer only pays for missing token accounts
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+    // Receiver of bid PDA rent
+    let bidder = next_account_info(account_iter)?;
+    let bidder_sell_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    // Protocol fee vault, ATA of config_pda for mint_buy
+    let fee_vault = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key != get_ata(auction_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_sell_ata matches calculated account
+    if *bidder_sell_ata.key != get_ata(bidder.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key != get_ata(bidder.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key != get_ata(seller.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that fee_vault matches calculated account
+    if *fee_vault.key != get_ata(config_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let fee_bps = get_fee_bps(program_id, config_pda)?;
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is a Batch auction
+    if auction.kind != AuctionKind::Batch {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction is cleared
+    if auction.clearing_price == 0 {
+        return Err(AuctionError::NotCleared.into());
+    }
+
+    // Check bid_pda is owned by this program
+    if bid_pda.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let bid = {
+        let data = bid_pda.data.borrow();
+        BatchBid::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_batch_bid_pda(program_id, auction_pda.key, bidder.key, bid.bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Amount of sell token filled and buy token paid at clearing price
+    // clearing_price <= bid.price, so paid <= escrow
+    let filled = get_batch_fill(&auction, &bid);
+    let paid =
+        get_buy_amt(filled, auction.clearing_price, auction.decimals_sell)?;
+    let escrow = get_buy_amt(bid.quantity, bid.price, auction.decimals_sell)?;
+
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &auction_id.to_le_bytes(),
+        &[bump],
+    ];
+
+    // Create seller_buy_ata if the seller closed it, it receives paid buy
+    // token and, after the last bid, buy token donated to auction_buy_ata
+    if (filled > 0 || auction.num_bids == 1) && seller_buy_ata.data_is_empty() {
+        create_ata(
+            payer,
+            mint_buy,
+            seller,
+            seller_buy_ata,
+            token_program,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
+    if filled > 0 {
+        // Send protocol fee to fee_vault
+        let fee = get_fee(paid, fee_bps);
+        if fee > 0 {
+            // Create fee_vault on first fee for mint_buy
+            if fee_vault.data_is_empty() {
+                create_ata(
+                    payer,
+                    mint_buy,
+                    config_pda,
+                    fee_vault,
+                    token_program,
+                    sys_program,
+                    ata_program,
+                    rent_sysvar,
+                )?;
+            }
+
+            transfer_from_pda(
+                token_program,
+                auction_buy_ata,
+                fee_vault,
+                auction_pda,
+                fee,
+                seeds,
+            )?;
+        }
+
+        // Send rest of paid buy token to seller
+        transfer_from_pda(
+            token_program,
+            auction_buy_ata,
+            seller_buy_ata,
+            auction_pda,
+            paid - fee,
+            seeds,
+        )?;
+
+        // Create bidder_sell_ata if it doesn't exist
+        if bidder_sell_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_sell,
+                bidder,
+                bidder_sell_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        // Send sell token to bidder
+        transfer_from_pda(
+            token_program,
+            auction_sell_ata,
+            bidder_sell_ata,
+            auction_pda,
+            filled,
+            seeds,
+        )?;
+    }
+
+    // Refund unused escrow to bidder
+    if escrow > paid {
+        // Create bidder_buy_ata if the bidder closed it
+        if bidder_buy_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_buy,
+                bidder,
+                bidder_buy_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        transfer_from_pda(
+            token_program,
+            auction_buy_ata,
+            bidder_buy_ata,
+            auction_pda,
+            escrow - paid,
+            seeds,
+        )?;
+    }
+
+    // Close bid_pda
+    close_pda(bid_pda, bidder, sys_program)?;
+
+    auction.remaining -= filled;
+    auction.num_bids -= 1;
+
+    if filled > 0 {
+        events::emit(&AuctionEvent::Filled(AuctionFilled {
+            auction: *auction_pda.key,
+            buyer: *bidder.key,
+            amount: filled,
+            price: auction.clearing_price,
+            buy_amt: paid,
+            remaining: auction.remaining,
+            timestamp: now,
+        }))?;
+    }
+
+    // Close auction after the last bid is settled
+    if auction.num_bids == 0 {
+        // Create seller_sell_ata if the seller closed it
+        if seller_sell_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_sell,
+                seller,
+                seller_sell_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        // Return unsold sell token to seller
+        let unsold = get_token_balance(auction_sell_ata)?;
+        if unsold > 0 {
+            transfer_from_pda(
+                token_program,
+                auction_sell_ata,
+                seller_sell_ata,
+                auction_pda,
+                unsold,
+                seeds,
+            )?;
+        }
+
+        // Every escrow is paid out or refunded, send buy token donated to
+        // auction_buy_ata to seller
+        sweep_and_close_ata(
+            token_program,
+            auction_buy_ata,
+            seller_buy_ata,
+            seller,
+            auction_pda,
+            seeds,
+        )?;
+        close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
+
+        // Close auction_pda
+        close_pda(auction_pda, seller, sys_program)?;
+
+        events::emit(&AuctionEvent::Closed(AuctionClosed {
+            auction: *auction_pda.key,
+            unsold,
+            timestamp: now,
+        }))?;
+
+        return Ok(());
+    }
+
+    // Store Auction state
+    let mut data = auction_pda.data.borrow_mut();
+    auction.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
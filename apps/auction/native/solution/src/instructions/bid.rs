@@ -0,0 +1,185 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    create_ata, get_ata, get_buy_amt, get_pda, get_token_balance, transfer,
+    transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionKind};
+
+pub fn bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Price of 1 sell token
+    price: u64,
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    // Previous highest bidder and its ATA receiving the refund, any account
+    // if there are no bids
+    let prev_bidder = next_account_info(account_iter)?;
+    let prev_bidder_buy_ata = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key != get_ata(auction_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key != get_ata(bidder.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is an English auction
+    if auction.kind != AuctionKind::English {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check price >= end_price
+    if price < auction.end_price {
+        return Err(AuctionError::BidTooLow.into());
+    }
+    // Check price > highest bid
+    let has_bid = auction.highest_bidder != Pubkey::default();
+    if has_bid && price <= auction.highest_bid {
+        return Err(AuctionError::BidTooLow.into());
+    }
+
+    // Calculate amount of buy token to escrow
+    let buy_amt = get_buy_amt(auction.sell_amt, price, auction.decimals_sell)?;
+    if buy_amt == 0 {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Create auction_buy_ata on first bid
+    if auction_buy_ata.data_is_empty() {
+        create_ata(
+            bidder,
+            mint_buy,
+            auction_pda,
+            auction_buy_ata,
+            token_program,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
+    // Refund previous highest bidder
+    if has_bid {
+        // Check prev_bidder is the highest bidder
+        if *prev_bidder.key != auction.highest_bidder {
+            return Err(AuctionError::NotHighestBidder.into());
+        }
+        // Check that prev_bidder_buy_ata matches calculated account
+        if *prev_bidder_buy_ata.key != get_ata(prev_bidder.key, mint_buy.key) {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+
+        // Create prev_bidder_buy_ata if the previous bidder closed it,
+        // otherwise a closed ATA would block every higher bid.
+        // Checks data, anyone can send lamports to the address.
+        if prev_bidder_buy_ata.data_is_empty() {
+            create_ata(
+                bidder,
+                mint_buy,
+                prev_bidder,
+                prev_bidder_buy_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        let seeds = &[
+            Auction::SEED_PREFIX,
+            seller.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &auction_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        // All buy token in escrow belongs to the previous highest bidder
+        let refund_amt = get_token_balance(auction_buy_ata)?;
+
+        transfer_from_pda(
+            token_program,
+            auction_buy_ata,
+            prev_bidder_buy_ata,
+            auction_pda,
+            refund_amt,
+            seeds,
+        )?;
+    }
+
+    // Send buy token to auction_buy_ata
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        auction_buy_ata,
+        bidder,
+        buy_amt,
+    )?;
+
+    // Store highest bid
+    auction.highest_bid = price;
+    auction.highest_bidder = *bidder.key;
+
+    let mut data = auction_pda.data.borrow_mut();
+    auction.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
@@ -7,14 +7,24 @@ use solana_program::{
 };
 
 use super::lib::{
-    close_ata, get_ata, get_pda, get_token_balance, transfer, transfer_from_pda,
+    close_pda, create_ata, create_pda, get_allocation_pda, get_ata,
+    get_buy_account, get_fee, get_fee_bps, get_pda, get_quote,
+    sweep_and_close_ata, transfer, transfer_buy, transfer_from_pda,
 };
-use crate::state::Auction;
+use crate::allowlist::{self, AllowlistProof, NO_CAP};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionClosed, AuctionEvent, AuctionFilled};
+use crate::metadata;
+use crate::state::{Allocation, Auction};
 
 pub fn buy(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    // Amount of sell token to buy
+    amount: u64,
     max_price: u64,
+    allowlist: Option<AllowlistProof>,
+    auction_id: u64,
     // Auction PDA bump
     bump: u8,
 ) -> Result<(), ProgramError> {
@@ -27,10 +37,17 @@ pub fn buy(
     let auction_pda = next_account_info(account_iter)?;
     let auction_sell_ata = next_account_info(account_iter)?;
     let buyer_sell_ata = next_account_info(account_iter)?;
+    // buyer and seller if paid in SOL
     let buyer_buy_ata = next_account_info(account_iter)?;
     let seller_buy_ata = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    // Protocol fee vault, ATA of config_pda for mint_buy or config_pda if
+    // paid in SOL
+    let fee_vault = next_account_info(account_iter)?;
     let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
     let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
 
     // Check buyer signed
     if !buyer.is_signer {
@@ -38,59 +55,219 @@ pub fn buy(
     }
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check that auction_sell_ata matches calculated account
     if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check that buyer_sell_ata matches calculated account
     if *buyer_sell_ata.key != get_ata(buyer.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check that buyer_buy_ata matches calculated account
-    if *buyer_buy_ata.key != get_ata(buyer.key, mint_buy.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *buyer_buy_ata.key != get_buy_account(buyer.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check that seller_buy_ata matches calculated account
-    if *seller_buy_ata.key != get_ata(seller.key, mint_buy.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *seller_buy_ata.key != get_buy_account(seller.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
+    // Check that fee_vault matches calculated account
+    if *fee_vault.key != get_buy_account(config_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let fee_bps = get_fee_bps(program_id, config_pda)?;
 
     let clock = Clock::get()?;
     let now: u64 = clock.unix_timestamp.try_into().unwrap();
 
-    let auction = {
+    let mut auction = {
         let data = auction_pda.data.borrow();
         Auction::try_from_slice(&data)?
     }; // Drop borrow here
 
-    // Check auction has started
-    assert!(auction.start_time <= now, "auction not started");
-    // Check auction has not ended
-    assert!(now < auction.end_time, "auction ended");
+    // Calculate price and amount of buy token, shared with quote
+    let (price, buy_amt) = get_quote(&auction, amount, now)?;
 
-    // Calculate price
-    let price_decrease = (auction.start_price - auction.end_price)
-        * (now - auction.start_time)
-        / (auction.end_time - auction.start_time);
+    // Check current price is less than or equal to max_price
+    if price > max_price {
+        return Err(AuctionError::MaxPrice.into());
+    }
 
-    let price = auction.start_price - price_decrease;
+    // Check buyer is in the allowlist
+    if auction.allowlist_root != [0; 32] {
+        let allowlist = allowlist.ok_or(AuctionError::InvalidAllowlist)?;
 
-    // Check current price is greater than or equal to end_price
-    assert!(price >= auction.end_price, "price < min");
+        let leaf = allowlist::get_leaf(buyer.key, allowlist.cap);
+        if !allowlist::verify(&auction.allowlist_root, &leaf, &allowlist.proof)
+        {
+            return Err(AuctionError::NotAllowed.into());
+        }
 
-    // Check current price is less than or equal to max_price
-    assert!(price <= max_price, "price > max");
+        // Track amount bought by buyer against cap
+        if allowlist.cap != NO_CAP {
+            // Last account, only needed for an allowlist entry with a cap
+            let allocation_pda = next_account_info(account_iter)?;
+
+            // Check that allocation_pda matches expected PDA
+            if *allocation_pda.key
+                != get_allocation_pda(
+                    program_id,
+                    auction_pda.key,
+                    buyer.key,
+                    allowlist.bump,
+                )?
+            {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            // Create allocation_pda on first buy
+            if allocation_pda.owner != program_id {
+                create_pda(
+                    program_id,
+                    buyer,
+                    allocation_pda,
+                    sys_program,
+                    Allocation::SPACE,
+                    &[
+                        Allocation::SEED_PREFIX,
+                        auction_pda.key.as_ref(),
+                        buyer.key.as_ref(),
+                        &[allowlist.bump],
+                    ],
+                )?;
+            }
+
+            let mut allocation = {
+                let data = allocation_pda.data.borrow();
+                Allocation::try_from_slice(&data)?
+            }; // Drop borrow here
+
+            // Check bought + amount <= cap
+            allocation.bought = allocation
+                .bought
+                .checked_add(amount)
+                .ok_or(AuctionError::MathOverflow)?;
+            if allocation.bought > allowlist.cap {
+                return Err(AuctionError::AllocationExceeded.into());
+            }
+
+            let mut data = allocation_pda.data.borrow_mut();
+            allocation.serialize(&mut &mut data[..])?;
+        }
+    }
+
+    // Send protocol fee to fee_vault
+    let fee = get_fee(buy_amt, fee_bps);
+    if fee > 0 {
+        // Create fee_vault on first fee for mint_buy, config_pda always
+        // exists if paid in SOL
+        if fee_vault.data_is_empty() {
+            create_ata(
+                buyer,
+                mint_buy,
+                config_pda,
+                fee_vault,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        transfer_buy(
+            token_program,
+            sys_program,
+            mint_buy,
+            buyer_buy_ata,
+            fee_vault,
+            buyer,
+            fee,
+        )?;
+    }
+
+    // Pay royalties to NFT creators
+    let mut royalty = 0;
+    if auction.nft {
+        // After allocation_pda, metadata of mint_sell followed by
+        // (creator, creator_buy_ata) of every creator
+        let metadata_account = next_account_info(account_iter)?;
+        let metadata = metadata::get_metadata(metadata_account, mint_sell.key)?;
+
+        // fee <= buy_amt
+        let royalties =
+            metadata::get_royalties(&metadata, buy_amt, buy_amt - fee);
+        for (creator, amt) in metadata.creators().iter().zip(royalties) {
+            let creator_account = next_account_info(account_iter)?;
+            let creator_buy_ata = next_account_info(account_iter)?;
+
+            // Check creator matches metadata
+            if *creator_account.key != creator.address {
+                return Err(AuctionError::InvalidCreator.into());
+            }
+            // Check that creator_buy_ata matches calculated account
+            if *creator_buy_ata.key
+                != get_ata(creator_account.key, mint_buy.key)
+            {
+                return Err(AuctionError::InvalidTokenAccount.into());
+            }
+
+            if amt == 0 {
+                continue;
+            }
 
-    // Calculate amount of buy token to send to seller
-    let sell_amt = get_token_balance(auction_sell_ata)?;
-    let buy_amt = sell_amt * price / (1e6 as u64);
+            // Create creator_buy_ata if it doesn't exist
+            if creator_buy_ata.data_is_empty() {
+                create_ata(
+                    buyer,
+                    mint_buy,
+                    creator_account,
+                    creator_buy_ata,
+                    token_program,
+                    sys_program,
+                    ata_program,
+                    rent_sysvar,
+                )?;
+            }
 
-    // Send buy token to seller
-    transfer(token_program, buyer_buy_ata, seller_buy_ata, buyer, buy_amt)?;
+            transfer(
+                token_program,
+                buyer_buy_ata,
+                creator_buy_ata,
+                buyer,
+                amt,
+            )?;
+
+            royalty += amt;
+        }
+    }
+
+    // Send rest of buy token to seller
+    let seller_amt = buy_amt
+        .checked_sub(fee)
+        .and_then(|amt| amt.checked_sub(royalty))
+        .ok_or(AuctionError::MathOverflow)?;
+
+    transfer_buy(
+        token_program,
+        sys_program,
+        mint_buy,
+        buyer_buy_ata,
+        seller_buy_ata,
+        buyer,
+        seller_amt,
+    )?;
 
     // Send sell token to buyer
     let seeds = &[
@@ -98,6 +275,7 @@ pub fn buy(
         seller.key.as_ref(),
         mint_sell.key.as_ref(),
         mint_buy.key.as_ref(),
+        &auction_id.to_le_bytes(),
         &[bump],
     ];
 
@@ -106,28 +284,49 @@ pub fn buy(
         auction_sell_ata,
         buyer_sell_ata,
         auction_pda,
-        sell_amt,
+        amount,
         seeds,
     )?;
 
-    // Close auction_sell_ata
-    close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
+    auction.remaining -= amount;
 
-    // Close auction_pda
-    // Get PDA balance and transfer lamports directly
-    let pda_lamports = auction_pda.lamports();
+    events::emit(&AuctionEvent::Filled(AuctionFilled {
+        auction: *auction_pda.key,
+        buyer: *buyer.key,
+        amount,
+        price,
+        buy_amt,
+        remaining: auction.remaining,
+        timestamp: now,
+    }))?;
 
-    **auction_pda.try_borrow_mut_lamports()? = 0;
-    **seller.try_borrow_mut_lamports()? = seller
-        .lamports()
-        .checked_add(pda_lamports)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if auction.remaining > 0 {
+        // Store remaining amount
+        let mut data = auction_pda.data.borrow_mut();
+        auction.serialize(&mut &mut data[..])?;
+
+        return Ok(());
+    }
 
-    // Clear out data
-    auction_pda.resize(0)?;
+    // Sold out
+    // Close auction_sell_ata, sell token sent to it by anyone goes to buyer
+    sweep_and_close_ata(
+        token_program,
+        auction_sell_ata,
+        buyer_sell_ata,
+        seller,
+        auction_pda,
+        seeds,
+    )?;
+
+    // Close auction_pda
+    close_pda(auction_pda, seller, sys_program)?;
 
-    // Assign the account to the System Program
-    auction_pda.assign(sys_program.key);
+    events::emit(&AuctionEvent::Closed(AuctionClosed {
+        auction: *auction_pda.key,
+        unsold: 0,
+        timestamp: now,
+    }))?;
 
     Ok(())
 }
@@ -1,17 +1,23 @@
+use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
 };
 
 use super::lib::{
-    close_ata, get_ata, get_pda, get_token_balance, transfer_from_pda,
+    close_ata, close_pda, get_ata, get_pda, get_token_balance,
+    transfer_from_pda,
 };
-use crate::state::Auction;
+use crate::error::AuctionError;
+use crate::events::{self, AuctionCancelled, AuctionEvent};
+use crate::state::{Auction, AuctionKind};
 
 pub fn cancel(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    auction_id: u64,
     // Auction PDA bump
     bump: u8,
 ) -> Result<(), ProgramError> {
@@ -32,19 +38,43 @@ pub fn cancel(
     }
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check that auction_sell_ata matches calculated account
     if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
-    // Check that buyer_sell_ata matches calculated account
+    // Check that seller_sell_ata matches calculated account
     if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
 
+    // Check English, Batch or Sealed auction has no bids, bidders' escrow
+    // would be locked
+    let auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    if (auction.kind == AuctionKind::English
+        && auction.highest_bidder != Pubkey::default())
+        || auction.num_bids > 0
+    {
+        return Err(AuctionError::HasBids.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
     // Get sell amount locked in auction_sell_ata
     let sell_amt = get_token_balance(auction_sell_ata)?;
 
@@ -54,6 +84,7 @@ pub fn cancel(
         seller.key.as_ref(),
         mint_sell.key.as_ref(),
         mint_buy.key.as_ref(),
+        &auction_id.to_le_bytes(),
         &[bump],
     ];
 
@@ -70,20 +101,14 @@ pub fn cancel(
     close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
 
     // Close auction_pda
-    // Get PDA balance and transfer lamports directly
-    let pda_lamports = auction_pda.lamports();
-
-    **auction_pda.try_borrow_mut_lamports()? = 0;
-    **seller.try_borrow_mut_lamports()? = seller
-        .lamports()
-        .checked_add(pda_lamports)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    // Clear out data
-    auction_pda.resize(0)?;
+    close_pda(auction_pda, seller, sys_program)?;
 
-    // Assign the account to the System Program
-    auction_pda.assign(sys_program.key);
+    events::emit(&AuctionEvent::Cancelled(AuctionCancelled {
+        auction: *auction_pda.key,
+        seller: *seller.key,
+        amount: sell_amt,
+        timestamp: now,
+    }))?;
 
     Ok(())
 }
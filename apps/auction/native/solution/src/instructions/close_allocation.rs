@@ -0,0 +1,46 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::lib::{close_pda, get_allocation_pda};
+
+pub fn close_allocation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Allocation PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Receiver of allocation PDA rent
+    let buyer = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let allocation_pda = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+
+    // Check buyer signed
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that allocation_pda matches expected PDA
+    if *allocation_pda.key
+        != get_allocation_pda(program_id, auction_pda.key, buyer.key, bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check allocation_pda is owned by this program
+    if allocation_pda.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    // Check auction is closed, the cap applies while it is open
+    if auction_pda.owner == program_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Close allocation_pda
+    close_pda(allocation_pda, buyer, sys_program)?;
+
+    Ok(())
+}
@@ -1,28 +1,69 @@
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
-    msg,
     program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
-    system_instruction,
     sysvar::{Sysvar, clock::Clock, rent::Rent},
 };
+use solana_system_interface::instruction as system_instruction;
 
-use super::lib::{create_ata, get_ata, get_pda, transfer};
-use crate::state::Auction;
+use super::lib::{
+    create_ata, get_ata, get_mint_decimals, get_mint_supply, get_pda, transfer,
+};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionCreated, AuctionEvent};
+use crate::metadata::{self, Collection};
+use crate::state::{Auction, AuctionKind, PriceCurve};
+
+// Parameters of Cmd::Init
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InitParams {
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub sell_amt: u64,
+    pub kind: AuctionKind,
+    pub curve: PriceCurve,
+    // Half life for Exponential, step interval for Step, 0 for Linear
+    pub curve_period: u64,
+    // Merkle root of buyers allowed to buy, None if anyone can buy
+    pub allowlist_root: Option<[u8; 32]>,
+    // Sealed auction - length of the reveal window after end_time
+    pub reveal_period: u64,
+    // Sell 1 NFT, royalties in its metadata are paid to creators
+    pub nft: bool,
+    // NFT must be a verified member of this collection, None = any
+    pub collection: Option<Pubkey>,
+    // Chosen by seller, allows concurrent auctions of the same mints
+    pub auction_id: u64,
+    // Auction PDA bump
+    pub bump: u8,
+}
 
 pub fn init(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    start_price: u64,
-    end_price: u64,
-    start_time: u64,
-    end_time: u64,
-    sell_amt: u64,
-    // Auction PDA bump
-    bump: u8,
+    params: InitParams,
 ) -> Result<(), ProgramError> {
+    let InitParams {
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        kind,
+        curve,
+        curve_period,
+        allowlist_root,
+        reveal_period,
+        nft,
+        collection,
+        auction_id,
+        bump,
+    } = params;
+
     let account_iter = &mut accounts.iter();
 
     let seller = next_account_info(account_iter)?;
@@ -42,40 +83,130 @@ pub fn init(
     }
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check auction_sell_ata
     if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check seller_sell_ata
     if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check sell token != buy token
     if *mint_sell.key == *mint_buy.key {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidMints.into());
     }
     // Check start_price >= end_price
     if start_price < end_price {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidPrices.into());
     }
     // Check now <= start_time < end_time
     let clock = Clock::get()?;
     let now: u64 = clock.unix_timestamp.try_into().unwrap();
-    if (start_time < now) || (end_time <= start_time) {
-        return Err(ProgramError::InvalidArgument);
+    if start_time < now {
+        return Err(AuctionError::InvalidStartTime.into());
+    }
+    if end_time <= start_time {
+        return Err(AuctionError::InvalidEndTime.into());
     }
     // Check sell_amt > 0
     if sell_amt == 0 {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidSellAmount.into());
     }
 
-    // 32 + 32 + 8 + 8 + 8 + 8 = 96
-    let space = 96;
-    let rent = Rent::get()?.minimum_balance(space);
+    // Check curve_period
+    match curve {
+        PriceCurve::Linear => {
+            if curve_period != 0 {
+                return Err(AuctionError::InvalidCurvePeriod.into());
+            }
+        }
+        PriceCurve::Exponential => {
+            if curve_period == 0 {
+                return Err(AuctionError::InvalidCurvePeriod.into());
+            }
+        }
+        // At least 1 step between start_time and end_time
+        PriceCurve::Step => {
+            if curve_period == 0 || end_time - start_time < curve_period {
+                return Err(AuctionError::InvalidCurvePeriod.into());
+            }
+        }
+    }
+
+    // Check auction paid in SOL is a Dutch auction without NFT, other kinds
+    // escrow buy token in token accounts
+    if *mint_buy.key == Auction::SOL_MINT {
+        if kind != AuctionKind::Dutch {
+            return Err(AuctionError::InvalidAuctionKind.into());
+        }
+        if nft {
+            return Err(AuctionError::InvalidNft.into());
+        }
+    }
+
+    // Check reveal_period > 0 only for Sealed auction
+    if (kind == AuctionKind::Sealed) != (reveal_period > 0) {
+        return Err(AuctionError::InvalidRevealPeriod.into());
+    }
+    // Check reveal_period <= MAX_REVEAL_PERIOD
+    if reveal_period > Auction::MAX_REVEAL_PERIOD {
+        return Err(AuctionError::InvalidRevealPeriod.into());
+    }
+
+    // Check NFT
+    if nft {
+        // Metadata of mint_sell, only passed for NFT
+        let metadata_account = next_account_info(account_iter)?;
+
+        // Check decimals = 0, supply = 1 and sell_amt = 1
+        if get_mint_decimals(mint_sell)? != 0
+            || get_mint_supply(mint_sell)? != 1
+            || sell_amt != 1
+        {
+            return Err(AuctionError::InvalidNft.into());
+        }
+        // Check Dutch auction, royalties are paid in buy
+        if kind != AuctionKind::Dutch {
+            return Err(AuctionError::InvalidAuctionKind.into());
+        }
+
+        let metadata = metadata::get_metadata(metadata_account, mint_sell.key)?;
+
+        // Check NFT is verified in collection
+        if let Some(collection) = collection {
+            let expected = Collection {
+                verified: true,
+                key: collection,
+            };
+            if metadata.collection != Some(expected) {
+                return Err(AuctionError::InvalidCollection.into());
+            }
+        }
+    } else if collection.is_some() {
+        return Err(AuctionError::InvalidCollection.into());
+    }
+
+    // Check allowlist_root != [0; 32], which means no allowlist
+    if allowlist_root == Some([0; 32]) {
+        return Err(AuctionError::InvalidAllowlist.into());
+    }
+    // Check allowlist is only set for Dutch auction, only buy checks it
+    if allowlist_root.is_some() && kind != AuctionKind::Dutch {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+
+    let rent = Rent::get()?.minimum_balance(Auction::SPACE as usize);
 
     // Create PDA account
     invoke_signed(
@@ -83,7 +214,7 @@ pub fn init(
             seller.key,
             auction_pda.key,
             rent,
-            space as u64,
+            Auction::SPACE,
             program_id,
         ),
         &[seller.clone(), auction_pda.clone(), sys_program.clone()],
@@ -92,6 +223,7 @@ pub fn init(
             seller.key.as_ref(),
             mint_sell.key.as_ref(),
             mint_buy.key.as_ref(),
+            &auction_id.to_le_bytes(),
             &[bump],
         ]],
     )?;
@@ -117,17 +249,70 @@ pub fn init(
         sell_amt,
     )?;
 
+    let decimals_buy = if *mint_buy.key == Auction::SOL_MINT {
+        Auction::SOL_DECIMALS
+    } else {
+        get_mint_decimals(mint_buy)?
+    };
+
     // Store Auction state
-    let mut data = auction_pda.data.borrow_mut();
     let auction = Auction {
+        seller: *seller.key,
+        auction_id,
+        kind,
+        curve,
+        curve_period,
+        allowlist_root: allowlist_root.unwrap_or([0; 32]),
         mint_sell: *mint_sell.key,
         mint_buy: *mint_buy.key,
+        decimals_sell: get_mint_decimals(mint_sell)?,
+        decimals_buy,
         start_price,
         end_price,
         start_time,
         end_time,
+        sell_amt,
+        remaining: sell_amt,
+        highest_bid: 0,
+        highest_bidder: Pubkey::default(),
+        highest_bid_pda: Pubkey::default(),
+        reveal_period,
+        nft,
+        collection: collection.unwrap_or_default(),
+        num_bids: 0,
+        bid_head: Pubkey::default(),
+        clearing_price: 0,
+        tie_supply: 0,
+        tie_demand: 0,
+        clear_next: Pubkey::default(),
+        clear_above: 0,
+        clear_price: 0,
     };
-    auction.serialize(&mut &mut data[..])?;
+
+    {
+        let mut data = auction_pda.data.borrow_mut();
+        auction.serialize(&mut &mut data[..])?;
+    }
+
+    events::emit(&AuctionEvent::Created(AuctionCreated {
+        auction: *auction_pda.key,
+        seller: *seller.key,
+        auction_id,
+        kind,
+        curve,
+        curve_period,
+        allowlist_root: auction.allowlist_root,
+        mint_sell: *mint_sell.key,
+        mint_buy: *mint_buy.key,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        reveal_period,
+        nft,
+        collection: auction.collection,
+    }))?;
 
     Ok(())
 }
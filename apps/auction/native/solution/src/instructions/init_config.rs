@@ -0,0 +1,74 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, rent::Rent},
+};
+use solana_system_interface::instruction as system_instruction;
+
+use super::lib::{get_config_pda, get_upgrade_authority};
+use crate::error::AuctionError;
+use crate::state::Config;
+
+pub fn init_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u16,
+    // Config PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Becomes the admin, must be the upgrade authority of this program
+    let admin = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    // Program data account of this program
+    let program_data = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+
+    // Check admin signed
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check admin is the upgrade authority, otherwise anyone could create
+    // the config first and become the admin
+    if get_upgrade_authority(program_id, program_data)? != Some(*admin.key) {
+        return Err(AuctionError::NotAdmin.into());
+    }
+    // Check that config_pda matches expected PDA
+    if *config_pda.key != get_config_pda(program_id, bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check fee_bps <= MAX_FEE_BPS
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(AuctionError::InvalidFee.into());
+    }
+
+    let rent = Rent::get()?.minimum_balance(Config::SPACE as usize);
+
+    // Create PDA account, fails if config already exists
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            config_pda.key,
+            rent,
+            Config::SPACE,
+            program_id,
+        ),
+        &[admin.clone(), config_pda.clone(), sys_program.clone()],
+        &[&[Config::SEED_PREFIX, &[bump]]],
+    )?;
+
+    // Store Config state
+    let mut data = config_pda.data.borrow_mut();
+    let config = Config {
+        admin: *admin.key,
+        fee_bps,
+        bump,
+    };
+    config.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
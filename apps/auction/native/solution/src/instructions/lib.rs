@@ -1,22 +1,31 @@
+use borsh::BorshDeserialize;
 use solana_address::Address;
 use solana_program::entrypoint::ProgramResult;
+use solana_program::hash::hashv;
 use solana_program::pubkey::{Pubkey, PubkeyError};
 use solana_program::{account_info::AccountInfo, program_error::ProgramError};
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     program::{invoke, invoke_signed},
+    sysvar::{Sysvar, rent::Rent},
 };
 use solana_program_pack::Pack;
+use solana_sdk_ids::bpf_loader_upgradeable;
+use solana_system_interface::instruction as system_instruction;
 use spl_associated_token_account_interface as spl_ata;
 use spl_token_interface;
 
-use crate::state::Auction;
+use crate::error::AuctionError;
+use crate::state::{
+    Allocation, Auction, AuctionKind, BatchBid, Config, PriceCurve, SealedBid,
+};
 
 pub fn get_pda(
     program_id: &Pubkey,
     seller: &Pubkey,
     mint_sell: &Pubkey,
     mint_buy: &Pubkey,
+    auction_id: u64,
     bump: u8,
 ) -> Result<Pubkey, PubkeyError> {
     Pubkey::create_program_address(
@@ -25,12 +34,199 @@ pub fn get_pda(
             seller.as_ref(),
             mint_sell.as_ref(),
             mint_buy.as_ref(),
+            &auction_id.to_le_bytes(),
             &[bump],
         ],
         program_id,
     )
 }
 
+// PDA of seeds with the canonical bump, fails for any other bump.
+// Otherwise a user could create one account per valid bump for the same
+// seeds.
+pub fn get_canonical_pda(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let (pda, canonical_bump) = Pubkey::find_program_address(seeds, program_id);
+    if bump != canonical_bump {
+        return Err(PubkeyError::InvalidSeeds);
+    }
+    Ok(pda)
+}
+
+pub fn get_allocation_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    buyer: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    get_canonical_pda(
+        program_id,
+        &[
+            Allocation::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.as_ref(),
+        ],
+        bump,
+    )
+}
+
+pub fn get_batch_bid_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    bidder: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    get_canonical_pda(
+        program_id,
+        &[BatchBid::SEED_PREFIX, auction_pda.as_ref(), bidder.as_ref()],
+        bump,
+    )
+}
+
+pub fn get_sealed_bid_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    bidder: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    get_canonical_pda(
+        program_id,
+        &[
+            SealedBid::SEED_PREFIX,
+            auction_pda.as_ref(),
+            bidder.as_ref(),
+        ],
+        bump,
+    )
+}
+
+pub fn get_config_pda(
+    program_id: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(&[Config::SEED_PREFIX, &[bump]], program_id)
+}
+
+// Read Config, checking that config_pda is the config PDA of this program
+pub fn get_config<'a>(
+    program_id: &Pubkey,
+    config_pda: &AccountInfo<'a>,
+) -> Result<Config, ProgramError> {
+    if config_pda.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let config = {
+        let data = config_pda.data.borrow();
+        Config::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    if *config_pda.key != get_config_pda(program_id, config.bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(config)
+}
+
+// Protocol fee of Config, 0 until the config is created by init_config
+pub fn get_fee_bps<'a>(
+    program_id: &Pubkey,
+    config_pda: &AccountInfo<'a>,
+) -> Result<u16, ProgramError> {
+    if config_pda.owner != program_id {
+        // Check that config_pda is the config PDA, so the fee can't be
+        // skipped by passing another account
+        let (pda, _) =
+            Pubkey::find_program_address(&[Config::SEED_PREFIX], program_id);
+        if *config_pda.key != pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        return Ok(0);
+    }
+
+    Ok(get_config(program_id, config_pda)?.fee_bps)
+}
+
+// Upgrade authority stored in the program data account of program_id,
+// None if the program is immutable.
+// Layout of UpgradeableLoaderState::ProgramData:
+//   variant (u32 = 3) | slot (u64) | option (u8) | authority (32)
+pub fn get_upgrade_authority<'a>(
+    program_id: &Pubkey,
+    program_data: &AccountInfo<'a>,
+) -> Result<Option<Pubkey>, ProgramError> {
+    // Check that program_data is the program data account of program_id
+    let (key, _) = Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &bpf_loader_upgradeable::ID,
+    );
+    if *program_data.key != key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check program_data is owned by the upgradeable loader
+    if *program_data.owner != bpf_loader_upgradeable::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = program_data.data.borrow();
+    if data.len() < 45 || data[0..4] != [3, 0, 0, 0] {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[12] == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Pubkey::try_from(&data[13..45]).unwrap()))
+}
+
+// Create PDA account of space bytes owned by program_id.
+// Anyone can send lamports to the PDA address before it is created, which
+// makes create_account fail, so a funded address is topped up to rent
+// exemption, allocated and assigned instead.
+// Fails if the account is already created.
+pub fn create_pda<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    pda: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    space: u64,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    if pda.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key, pda.key, rent, space, program_id,
+            ),
+            &[payer.clone(), pda.clone(), sys_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let top_up = rent.saturating_sub(pda.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, pda.key, top_up),
+            &[payer.clone(), pda.clone(), sys_program.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(pda.key, space),
+        &[pda.clone(), sys_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(pda.key, program_id),
+        &[pda.clone(), sys_program.clone()],
+        &[signer_seeds],
+    )
+}
+
 pub fn create_ata<'a>(
     payer: &AccountInfo<'a>,
     mint: &AccountInfo<'a>,
@@ -124,6 +320,34 @@ pub fn close_ata<'a>(
     )
 }
 
+// Send the whole balance of ata to dst, then close ata.
+// Anyone can send tokens to ata, closing fails unless it is empty.
+pub fn sweep_and_close_ata<'a>(
+    token_program: &AccountInfo<'a>,
+    ata: &AccountInfo<'a>,
+    // Receiver of tokens left in ata
+    dst: &AccountInfo<'a>,
+    // Receiver of ata rent
+    rent_dst: &AccountInfo<'a>,
+    // ATA owner
+    owner: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let amount = get_token_balance(ata)?;
+    if amount > 0 {
+        transfer_from_pda(
+            token_program,
+            ata,
+            dst,
+            owner,
+            amount,
+            signer_seeds,
+        )?;
+    }
+
+    close_ata(token_program, ata, rent_dst, owner, signer_seeds)
+}
+
 pub fn get_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
     let addr = spl_ata::address::get_associated_token_address(
         &Address::from(wallet.to_bytes()),
@@ -132,6 +356,37 @@ pub fn get_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
     Pubkey::from(addr.to_bytes())
 }
 
+// Account holding buy token of owner, owner itself if paid in SOL
+pub fn get_buy_account(owner: &Pubkey, mint_buy: &Pubkey) -> Pubkey {
+    if *mint_buy == Auction::SOL_MINT {
+        *owner
+    } else {
+        get_ata(owner, mint_buy)
+    }
+}
+
+// Transfer buy token from src owned by auth
+// If paid in SOL, src = auth and lamports are sent through the System Program
+pub fn transfer_buy<'a>(
+    token_program: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    mint_buy: &AccountInfo<'a>,
+    src: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    // Transfer authority
+    auth: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    if *mint_buy.key != Auction::SOL_MINT {
+        return transfer(token_program, src, dst, auth, amount);
+    }
+
+    invoke(
+        &system_instruction::transfer(auth.key, dst.key, amount),
+        &[auth.clone(), dst.clone(), sys_program.clone()],
+    )
+}
+
 pub fn transfer<'a>(
     token_program: &AccountInfo<'a>,
     src: &AccountInfo<'a>,
@@ -231,3 +486,180 @@ pub fn get_token_balance<'a>(
 
     Ok(token_account_data.amount)
 }
+
+pub fn close_pda<'a>(
+    pda: &AccountInfo<'a>,
+    // Receiver of PDA rent
+    dst: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    // Get PDA balance and transfer lamports directly
+    let pda_lamports = pda.lamports();
+
+    **pda.try_borrow_mut_lamports()? = 0;
+    **dst.try_borrow_mut_lamports()? = dst
+        .lamports()
+        .checked_add(pda_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Clear out data
+    pda.resize(0)?;
+
+    // Assign the account to the System Program
+    pda.assign(sys_program.key);
+
+    Ok(())
+}
+
+pub fn get_mint_supply<'a>(
+    mint: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    let mint_data =
+        spl_token_interface::state::Mint::unpack(&mint.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(mint_data.supply)
+}
+
+pub fn get_mint_decimals<'a>(
+    mint: &AccountInfo<'a>,
+) -> Result<u8, ProgramError> {
+    let mint_data =
+        spl_token_interface::state::Mint::unpack(&mint.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(mint_data.decimals)
+}
+
+// Amount of buy token to pay for sell_amt at price
+// price = buy token units per 1 whole sell token (10^decimals_sell units)
+// Rounded up so that the seller never receives less than the price
+pub fn get_buy_amt(
+    sell_amt: u64,
+    price: u64,
+    decimals_sell: u8,
+) -> Result<u64, ProgramError> {
+    let one = 10u128
+        .checked_pow(decimals_sell as u32)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let buy_amt = (sell_amt as u128 * price as u128).div_ceil(one);
+
+    u64::try_from(buy_amt).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+// Dutch auction price at now, start_time <= now <= end_time
+// Prices use u128 intermediates so that no curve can overflow
+pub fn get_price(auction: &Auction, now: u64) -> Result<u64, ProgramError> {
+    if now < auction.start_time || auction.end_time < now {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let diff = (auction.start_price - auction.end_price) as u128;
+    let dt = (now - auction.start_time) as u128;
+    let duration = (auction.end_time - auction.start_time) as u128;
+    let period = auction.curve_period as u128;
+
+    let price_decrease = match auction.curve {
+        PriceCurve::Linear => diff * dt / duration,
+        PriceCurve::Exponential => {
+            if period == 0 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            // Number of full halvings and time into the current one
+            let n = dt / period;
+            let r = dt % period;
+            if n >= 128 {
+                diff
+            } else {
+                // Remaining distance to end_price after n halvings,
+                // linearly interpolated towards the next halving
+                let d = diff >> n;
+                let d = d - (d - (d >> 1)) * r / period;
+                diff - d
+            }
+        }
+        PriceCurve::Step => {
+            if period == 0 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            let steps = duration / period;
+            if steps == 0 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            diff * (dt / period).min(steps) / steps
+        }
+    };
+
+    // price_decrease <= diff <= start_price
+    Ok(auction.start_price - price_decrease as u64)
+}
+
+// Price and amount of buy token to pay for amount of sell token of a Dutch
+// auction at now. Used by buy and quote so that both always agree.
+// Returns (price, buy_amt)
+pub fn get_quote(
+    auction: &Auction,
+    amount: u64,
+    now: u64,
+) -> Result<(u64, u64), ProgramError> {
+    // Check auction is a Dutch auction
+    if auction.kind != AuctionKind::Dutch {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+
+    let price = get_price(auction, now)?;
+
+    // Check current price is greater than or equal to end_price
+    if price < auction.end_price {
+        return Err(AuctionError::InvalidCurrentPrice.into());
+    }
+    // Check 0 < amount <= remaining
+    if amount == 0 || amount > auction.remaining {
+        return Err(AuctionError::InvalidAmount.into());
+    }
+
+    let buy_amt = get_buy_amt(amount, price, auction.decimals_sell)?;
+    if buy_amt == 0 {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    Ok((price, buy_amt))
+}
+
+// Protocol fee on buy_amt, rounded down
+pub fn get_fee(buy_amt: u64, fee_bps: u16) -> u64 {
+    // buy_amt * fee_bps / 10000 <= buy_amt
+    (buy_amt as u128 * fee_bps as u128 / 10000) as u64
+}
+
+// Amount of sell token filled for a batch bid once the auction is cleared
+pub fn get_batch_fill(auction: &Auction, bid: &BatchBid) -> u64 {
+    if bid.price > auction.clearing_price {
+        bid.quantity
+    } else if bid.price == auction.clearing_price {
+        // Pro rata, rounded down, unfilled dust is returned to the seller
+        (bid.quantity as u128 * auction.tie_supply as u128 / auction.tie_demand)
+            as u64
+    } else {
+        0
+    }
+}
+
+// Commitment of a sealed bid
+// bidder is hashed so that a commitment can't be copied by another bidder
+pub fn get_commitment(
+    bidder: &Pubkey,
+    price: u64,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[bidder.as_ref(), &price.to_le_bytes(), salt]).to_bytes()
+}
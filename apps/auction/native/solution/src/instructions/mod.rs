@@ -1,8 +1,38 @@
+pub mod batch_bid;
+pub mod batch_clear;
+pub mod batch_settle;
+pub mod bid;
 pub mod buy;
 pub mod cancel;
+pub mod close_allocation;
 pub mod init;
+pub mod init_config;
 pub mod lib;
+pub mod quote;
+pub mod reclaim;
+pub mod sealed_commit;
+pub mod sealed_reveal;
+pub mod sealed_settle;
+pub mod set_fee;
+pub mod settle;
+pub mod update;
+pub mod withdraw_fees;
 
+pub use batch_bid::*;
+pub use batch_clear::*;
+pub use batch_settle::*;
+pub use bid::*;
 pub use buy::*;
 pub use cancel::*;
+pub use close_allocation::*;
 pub use init::*;
+pub use init_config::*;
+pub use quote::*;
+pub use reclaim::*;
+pub use sealed_commit::*;
+pub use sealed_reveal::*;
+pub use sealed_settle::*;
+pub use set_fee::*;
+pub use settle::*;
+pub use update::*;
+pub use withdraw_fees::*;
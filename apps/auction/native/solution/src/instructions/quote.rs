@@ -0,0 +1,79 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::{get_return_data, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{get_pda, get_quote};
+use crate::state::Auction;
+
+// Return data of GetPrice
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Quote {
+    // Current price of 1 sell token
+    pub price: u64,
+    // Amount of buy token that buy would charge for amount, including fees
+    pub buy_amt: u64,
+}
+
+// GetPrice, read-only, can be called directly or through CPI
+pub fn quote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Amount of sell token to buy
+    amount: u64,
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    let (price, buy_amt) = get_quote(&auction, amount, now)?;
+
+    set_return_data(&borsh::to_vec(&Quote { price, buy_amt })?);
+
+    Ok(())
+}
+
+// Read Quote after a CPI to GetPrice of the auction program program_id
+pub fn read_quote(program_id: &Pubkey) -> Result<Quote, ProgramError> {
+    let (caller, data) =
+        get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+
+    // Check return data was set by the auction program
+    if caller != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(Quote::try_from_slice(&data)?)
+}
@@ -0,0 +1,145 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    close_ata, close_pda, create_ata, get_ata, get_pda, get_token_balance,
+    transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionClosed, AuctionEvent};
+use crate::state::Auction;
+
+pub fn reclaim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can reclaim, payer receives the bounty
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction has ended
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+    // Check there are no bids, English, Batch or Sealed auction with bids
+    // must be settled
+    if auction.highest_bidder != Pubkey::default() || auction.num_bids > 0 {
+        return Err(AuctionError::HasBids.into());
+    }
+
+    // Create seller_sell_ata if the seller closed it
+    if seller_sell_ata.data_is_empty() {
+        create_ata(
+            payer,
+            mint_sell,
+            seller,
+            seller_sell_ata,
+            token_program,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
+    // Get sell amount locked in auction_sell_ata
+    let sell_amt = get_token_balance(auction_sell_ata)?;
+
+    // Send sell token to seller
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &auction_id.to_le_bytes(),
+        &[bump],
+    ];
+
+    transfer_from_pda(
+        token_program,
+        auction_sell_ata,
+        seller_sell_ata,
+        auction_pda,
+        sell_amt,
+        seeds,
+    )?;
+
+    // Close auction_sell_ata
+    close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
+
+    // Pay bounty from auction_pda rent, no bounty if seller reclaims
+    if payer.key != seller.key {
+        let bounty = (auction_pda.lamports() as u128
+            * Auction::RECLAIM_BOUNTY_BPS as u128
+            / 10000) as u64;
+
+        **auction_pda.try_borrow_mut_lamports()? -= bounty;
+        **payer.try_borrow_mut_lamports()? = payer
+            .lamports()
+            .checked_add(bounty)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    // Close auction_pda, rest of the rent goes to seller
+    close_pda(auction_pda, seller, sys_program)?;
+
+    events::emit(&AuctionEvent::Closed(AuctionClosed {
+        auction: *auction_pda.key,
+        unsold: sell_amt,
+        timestamp: now,
+    }))?;
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    create_ata, create_pda, get_ata, get_pda, get_sealed_bid_pda, transfer,
+};
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionKind, SealedBid};
+
+pub fn sealed_commit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // lib::get_commitment(bidder, price, salt)
+    commitment: [u8; 32],
+    // Amount of buy token to escrow, must cover the price to be revealed
+    deposit: u64,
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+    // Sealed bid PDA bump
+    bid_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_sealed_bid_pda(
+            program_id,
+            auction_pda.key,
+            bidder.key,
+            bid_bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key != get_ata(auction_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key != get_ata(bidder.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is a Sealed auction
+    if auction.kind != AuctionKind::Sealed {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check bid phase has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check deposit > 0
+    if deposit == 0 {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Create bid PDA, fails if bidder already committed
+    create_pda(
+        program_id,
+        bidder,
+        bid_pda,
+        sys_program,
+        SealedBid::SPACE,
+        &[
+            SealedBid::SEED_PREFIX,
+            auction_pda.key.as_ref(),
+            bidder.key.as_ref(),
+            &[bid_bump],
+        ],
+    )?;
+
+    // Create auction_buy_ata on first commit
+    if auction_buy_ata.data_is_empty() {
+        create_ata(
+            bidder,
+            mint_buy,
+            auction_pda,
+            auction_buy_ata,
+            token_program,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
+    // Send deposit to auction_buy_ata
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        auction_buy_ata,
+        bidder,
+        deposit,
+    )?;
+
+    // Store SealedBid state
+    {
+        let mut data = bid_pda.data.borrow_mut();
+        let bid = SealedBid {
+            bidder: *bidder.key,
+            commitment,
+            deposit,
+            price: 0,
+            revealed: false,
+            bump: bid_bump,
+        };
+        bid.serialize(&mut &mut data[..])?;
+    }
+
+    // Store Auction state
+    auction.num_bids += 1;
+
+    let mut data = auction_pda.data.borrow_mut();
+    auction.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
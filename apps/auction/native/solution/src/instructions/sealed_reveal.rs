@@ -0,0 +1,133 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{get_buy_amt, get_commitment, get_pda, get_sealed_bid_pda};
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionKind, SealedBid};
+
+pub fn sealed_reveal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Price of 1 sell token
+    price: u64,
+    salt: [u8; 32],
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check bid_pda is owned by this program
+    if bid_pda.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut bid = {
+        let data = bid_pda.data.borrow();
+        SealedBid::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_sealed_bid_pda(
+            program_id,
+            auction_pda.key,
+            bidder.key,
+            bid.bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is a Sealed auction
+    if auction.kind != AuctionKind::Sealed {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check end_time <= now < end_time + reveal_period
+    let reveal_end = auction
+        .end_time
+        .checked_add(auction.reveal_period)
+        .ok_or(AuctionError::MathOverflow)?;
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+    if reveal_end <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check bid is not revealed yet
+    if bid.revealed {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Check price and salt match commitment
+    if get_commitment(bidder.key, price, &salt) != bid.commitment {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // Check price >= end_price
+    if price < auction.end_price {
+        return Err(AuctionError::BidTooLow.into());
+    }
+    // Check deposit covers the price of the whole lot
+    let buy_amt = get_buy_amt(auction.sell_amt, price, auction.decimals_sell)?;
+    if buy_amt == 0 || buy_amt > bid.deposit {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Store SealedBid state
+    bid.price = price;
+    bid.revealed = true;
+
+    {
+        let mut data = bid_pda.data.borrow_mut();
+        bid.serialize(&mut &mut data[..])?;
+    }
+
+    // Store highest bid, first reveal wins ties
+    let has_bid = auction.highest_bidder != Pubkey::default();
+    if !has_bid || price > auction.highest_bid {
+        auction.highest_bid = price;
+        auction.highest_bidder = *bidder.key;
+        auction.highest_bid_pda = *bid_pda.key;
+
+        let mut data = auction_pda.data.borrow_mut();
+        auction.serialize(&mut &mut data[..])?;
+    }
+
+    Ok(())
+}
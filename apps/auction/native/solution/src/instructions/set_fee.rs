@@ -0,0 +1,45 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::lib::get_config;
+use crate::error::AuctionError;
+use crate::state::Config;
+
+pub fn set_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u16,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+
+    // Check admin signed
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut config = get_config(program_id, config_pda)?;
+
+    // Check admin matches config
+    if *admin.key != config.admin {
+        return Err(AuctionError::NotAdmin.into());
+    }
+    // Check fee_bps <= MAX_FEE_BPS
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(AuctionError::InvalidFee.into());
+    }
+
+    // Store Config state
+    config.fee_bps = fee_bps;
+
+    let mut data = config_pda.data.borrow_mut();
+    config.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
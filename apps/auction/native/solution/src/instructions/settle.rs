@@ -0,0 +1,256 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    close_ata, close_pda, create_ata, get_ata, get_fee, get_fee_bps, get_pda,
+    get_token_balance, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionClosed, AuctionEvent, AuctionFilled};
+use crate::state::{Auction, AuctionKind};
+
+pub fn settle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can settle, payer only pays for missing token accounts
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let winner = next_account_info(account_iter)?;
+    let winner_sell_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    // Protocol fee vault, ATA of config_pda for mint_buy
+    let fee_vault = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key != get_ata(auction_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key != get_ata(seller.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that fee_vault matches calculated account
+    if *fee_vault.key != get_ata(config_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let fee_bps = get_fee_bps(program_id, config_pda)?;
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction is an English auction
+    if auction.kind != AuctionKind::English {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check auction has ended
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &auction_id.to_le_bytes(),
+        &[bump],
+    ];
+
+    let sell_amt = get_token_balance(auction_sell_ata)?;
+    let mut unsold = 0;
+
+    if auction.highest_bidder != Pubkey::default() {
+        // Check winner is the highest bidder
+        if *winner.key != auction.highest_bidder {
+            return Err(AuctionError::NotHighestBidder.into());
+        }
+        // Check that winner_sell_ata matches calculated account
+        if *winner_sell_ata.key != get_ata(winner.key, mint_sell.key) {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+
+        let buy_amt = get_token_balance(auction_buy_ata)?;
+
+        // Send protocol fee to fee_vault
+        let fee = get_fee(buy_amt, fee_bps);
+        if fee > 0 {
+            // Create fee_vault on first fee for mint_buy
+            if fee_vault.data_is_empty() {
+                create_ata(
+                    payer,
+                    mint_buy,
+                    config_pda,
+                    fee_vault,
+                    token_program,
+                    sys_program,
+                    ata_program,
+                    rent_sysvar,
+                )?;
+            }
+
+            transfer_from_pda(
+                token_program,
+                auction_buy_ata,
+                fee_vault,
+                auction_pda,
+                fee,
+                seeds,
+            )?;
+        }
+
+        // Create seller_buy_ata if the seller closed it
+        if seller_buy_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_buy,
+                seller,
+                seller_buy_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        // Send rest of escrowed buy token to seller
+        transfer_from_pda(
+            token_program,
+            auction_buy_ata,
+            seller_buy_ata,
+            auction_pda,
+            buy_amt - fee,
+            seeds,
+        )?;
+
+        // Close auction_buy_ata
+        close_ata(token_program, auction_buy_ata, seller, auction_pda, seeds)?;
+
+        // Create winner_sell_ata if it doesn't exist.
+        // Checks data, anyone can send lamports to the address.
+        if winner_sell_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_sell,
+                winner,
+                winner_sell_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        // Send sell token to winner
+        transfer_from_pda(
+            token_program,
+            auction_sell_ata,
+            winner_sell_ata,
+            auction_pda,
+            sell_amt,
+            seeds,
+        )?;
+
+        events::emit(&AuctionEvent::Filled(AuctionFilled {
+            auction: *auction_pda.key,
+            buyer: *winner.key,
+            amount: sell_amt,
+            price: auction.highest_bid,
+            buy_amt,
+            remaining: 0,
+            timestamp: now,
+        }))?;
+    } else {
+        // Create seller_sell_ata if the seller closed it
+        if seller_sell_ata.data_is_empty() {
+            create_ata(
+                payer,
+                mint_sell,
+                seller,
+                seller_sell_ata,
+                token_program,
+                sys_program,
+                ata_program,
+                rent_sysvar,
+            )?;
+        }
+
+        // No bids, return sell token to seller
+        transfer_from_pda(
+            token_program,
+            auction_sell_ata,
+            seller_sell_ata,
+            auction_pda,
+            sell_amt,
+            seeds,
+        )?;
+
+        unsold = sell_amt;
+    }
+
+    // Close auction_sell_ata
+    close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
+
+    // Close auction_pda
+    close_pda(auction_pda, seller, sys_program)?;
+
+    events::emit(&AuctionEvent::Closed(AuctionClosed {
+        auction: *auction_pda.key,
+        unsold,
+        timestamp: now,
+    }))?;
+
+    Ok(())
+}
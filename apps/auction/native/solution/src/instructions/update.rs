@@ -0,0 +1,138 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{get_ata, get_pda, transfer};
+use crate::error::AuctionError;
+use crate::events::{self, AuctionEvent, AuctionUpdated};
+use crate::state::{Auction, AuctionKind};
+
+pub fn update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // New end_price, must be <= current end_price
+    end_price: Option<u64>,
+    // New end_time, must be >= current end_time
+    end_time: Option<u64>,
+    // Amount of sell token to add to the lot
+    top_up: Option<u64>,
+    auction_id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+
+    // Check seller signed
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            auction_id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let mut auction = {
+        let data = auction_pda.data.borrow();
+        Auction::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check nobody has bought or bid yet
+    let has_buy = match auction.kind {
+        AuctionKind::Dutch => auction.remaining < auction.sell_amt,
+        AuctionKind::English => auction.highest_bidder != Pubkey::default(),
+        AuctionKind::Batch | AuctionKind::Sealed => auction.num_bids > 0,
+    };
+    if has_buy {
+        return Err(AuctionError::HasBids.into());
+    }
+
+    // Lower end_price, start_price >= end_price still holds
+    if let Some(end_price) = end_price {
+        if end_price > auction.end_price {
+            return Err(AuctionError::InvalidPrices.into());
+        }
+        auction.end_price = end_price;
+    }
+
+    // Extend end_time, end_time > start_time still holds
+    if let Some(end_time) = end_time {
+        if end_time < auction.end_time {
+            return Err(AuctionError::InvalidEndTime.into());
+        }
+        auction.end_time = end_time;
+    }
+
+    // Top up sell_amt
+    if let Some(top_up) = top_up {
+        if top_up == 0 {
+            return Err(AuctionError::InvalidSellAmount.into());
+        }
+
+        // Send sell token to auction_sell_ata
+        transfer(
+            token_program,
+            seller_sell_ata,
+            auction_sell_ata,
+            seller,
+            top_up,
+        )?;
+
+        auction.sell_amt = auction
+            .sell_amt
+            .checked_add(top_up)
+            .ok_or(AuctionError::MathOverflow)?;
+        auction.remaining = auction.sell_amt;
+    }
+
+    // Store Auction state
+    {
+        let mut data = auction_pda.data.borrow_mut();
+        auction.serialize(&mut &mut data[..])?;
+    }
+
+    events::emit(&AuctionEvent::Updated(AuctionUpdated {
+        auction: *auction_pda.key,
+        end_price: auction.end_price,
+        end_time: auction.end_time,
+        sell_amt: auction.sell_amt,
+        timestamp: now,
+    }))?;
+
+    Ok(())
+}
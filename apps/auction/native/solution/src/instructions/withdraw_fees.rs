@@ -0,0 +1,73 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, rent::Rent},
+};
+
+use super::lib::{get_buy_account, get_config, transfer_from_pda};
+use crate::error::AuctionError;
+use crate::state::{Auction, Config};
+
+pub fn withdraw_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    // config_pda if paid in SOL
+    let fee_vault = next_account_info(account_iter)?;
+    // Token account of mint_buy receiving the fees, any account for SOL
+    let dst = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+
+    // Check admin signed
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let config = get_config(program_id, config_pda)?;
+
+    // Check admin matches config
+    if *admin.key != config.admin {
+        return Err(AuctionError::NotAdmin.into());
+    }
+    // Check that fee_vault matches calculated account
+    if *fee_vault.key != get_buy_account(config_pda.key, mint_buy.key) {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    // SOL fees are kept in config_pda above its rent exempt minimum
+    if *mint_buy.key == Auction::SOL_MINT {
+        let rent = Rent::get()?.minimum_balance(config_pda.data_len());
+        if amount > config_pda.lamports().saturating_sub(rent) {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        **config_pda.try_borrow_mut_lamports()? -= amount;
+        **dst.try_borrow_mut_lamports()? =
+            dst.lamports()
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        return Ok(());
+    }
+
+    // Send fees to dst
+    let seeds = &[Config::SEED_PREFIX.as_ref(), &[config.bump]];
+
+    transfer_from_pda(
+        token_program,
+        fee_vault,
+        dst,
+        config_pda,
+        amount,
+        seeds,
+    )?;
+
+    Ok(())
+}
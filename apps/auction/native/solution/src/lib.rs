@@ -1,35 +1,142 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
-    pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey,
 };
 
+pub mod allowlist;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod error;
+pub mod events;
+// Off-chain only, not built into the program
+#[cfg(not(target_os = "solana"))]
+pub mod history;
 pub mod instructions;
+pub mod metadata;
 pub mod state;
 
+use allowlist::AllowlistProof;
+use instructions::InitParams;
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub enum Cmd {
-    Init {
-        start_price: u64,
-        end_price: u64,
-        start_time: u64,
-        end_time: u64,
-        sell_amt: u64,
-        // Auction PDA bump
-        bump: u8,
-    },
+    Init(InitParams),
     Buy {
+        // Amount of sell token to buy
+        amount: u64,
         max_price: u64,
+        // Required if the auction has an allowlist
+        allowlist: Option<AllowlistProof>,
+        auction_id: u64,
         // Auction PDA bump
         bump: u8,
     },
     Cancel {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    Bid {
+        price: u64,
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    Settle {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    Reclaim {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    InitConfig {
+        // Protocol fee, 100 = 1%
+        fee_bps: u16,
+        // Config PDA bump
+        bump: u8,
+    },
+    SetFee {
+        fee_bps: u16,
+    },
+    WithdrawFees {
+        amount: u64,
+    },
+    Update {
+        // None = keep current value
+        end_price: Option<u64>,
+        end_time: Option<u64>,
+        // Amount of sell token to add
+        top_up: Option<u64>,
+        auction_id: u64,
         // Auction PDA bump
         bump: u8,
     },
+    BatchBid {
+        // Amount of sell token to buy
+        quantity: u64,
+        // Max price of 1 sell token
+        price: u64,
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+        // Batch bid PDA bump
+        bid_bump: u8,
+    },
+    BatchClear {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    BatchSettle {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    SealedCommit {
+        // hash(bidder, price, salt), see instructions::lib::get_commitment
+        commitment: [u8; 32],
+        // Amount of buy token to escrow
+        deposit: u64,
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+        // Sealed bid PDA bump
+        bid_bump: u8,
+    },
+    SealedReveal {
+        // Price of 1 sell token
+        price: u64,
+        salt: [u8; 32],
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    SealedSettle {
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    // Return data is instructions::Quote
+    GetPrice {
+        // Amount of sell token to buy
+        amount: u64,
+        auction_id: u64,
+        // Auction PDA bump
+        bump: u8,
+    },
+    // Refund rent of a buyer's allocation PDA once the auction is closed
+    CloseAllocation {
+        // Allocation PDA bump
+        bump: u8,
+    },
 }
 
-entrypoint!(process_instruction);
+// Programs depending on this crate enable no-entrypoint
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -39,30 +146,114 @@ pub fn process_instruction(
     let ix = Cmd::try_from_slice(instruction_data)?;
 
     match ix {
-        Cmd::Init {
-            start_price,
+        Cmd::Init(params) => {
+            instructions::init(program_id, accounts, params)?;
+        }
+        Cmd::Buy {
+            amount,
+            max_price,
+            allowlist,
+            auction_id,
+            bump,
+        } => {
+            instructions::buy(
+                program_id, accounts, amount, max_price, allowlist, auction_id,
+                bump,
+            )?;
+        }
+        Cmd::Cancel { auction_id, bump } => {
+            instructions::cancel(program_id, accounts, auction_id, bump)?;
+        }
+        Cmd::Bid {
+            price,
+            auction_id,
+            bump,
+        } => {
+            instructions::bid(program_id, accounts, price, auction_id, bump)?;
+        }
+        Cmd::Settle { auction_id, bump } => {
+            instructions::settle(program_id, accounts, auction_id, bump)?;
+        }
+        Cmd::Reclaim { auction_id, bump } => {
+            instructions::reclaim(program_id, accounts, auction_id, bump)?;
+        }
+        Cmd::InitConfig { fee_bps, bump } => {
+            instructions::init_config(program_id, accounts, fee_bps, bump)?;
+        }
+        Cmd::SetFee { fee_bps } => {
+            instructions::set_fee(program_id, accounts, fee_bps)?;
+        }
+        Cmd::WithdrawFees { amount } => {
+            instructions::withdraw_fees(program_id, accounts, amount)?;
+        }
+        Cmd::Update {
             end_price,
-            start_time,
             end_time,
-            sell_amt,
+            top_up,
+            auction_id,
             bump,
         } => {
-            instructions::init(
-                program_id,
-                accounts,
-                start_price,
-                end_price,
-                start_time,
-                end_time,
-                sell_amt,
+            instructions::update(
+                program_id, accounts, end_price, end_time, top_up, auction_id,
                 bump,
             )?;
         }
-        Cmd::Buy { max_price, bump } => {
-            instructions::buy(program_id, accounts, max_price, bump)?;
+        Cmd::BatchBid {
+            quantity,
+            price,
+            auction_id,
+            bump,
+            bid_bump,
+        } => {
+            instructions::batch_bid(
+                program_id, accounts, quantity, price, auction_id, bump,
+                bid_bump,
+            )?;
+        }
+        Cmd::BatchClear { auction_id, bump } => {
+            instructions::batch_clear(program_id, accounts, auction_id, bump)?;
+        }
+        Cmd::BatchSettle { auction_id, bump } => {
+            instructions::batch_settle(program_id, accounts, auction_id, bump)?;
+        }
+        Cmd::SealedCommit {
+            commitment,
+            deposit,
+            auction_id,
+            bump,
+            bid_bump,
+        } => {
+            instructions::sealed_commit(
+                program_id, accounts, commitment, deposit, auction_id, bump,
+                bid_bump,
+            )?;
+        }
+        Cmd::SealedReveal {
+            price,
+            salt,
+            auction_id,
+            bump,
+        } => {
+            instructions::sealed_reveal(
+                program_id, accounts, price, salt, auction_id, bump,
+            )?;
+        }
+        Cmd::SealedSettle { auction_id, bump } => {
+            instructions::sealed_settle(
+                program_id, accounts, auction_id, bump,
+            )?;
+        }
+        Cmd::GetPrice {
+            amount,
+            auction_id,
+            bump,
+        } => {
+            instructions::quote(
+                program_id, accounts, amount, auction_id, bump,
+            )?;
         }
-        Cmd::Cancel { bump } => {
-            instructions::cancel(program_id, accounts, bump)?;
+        Cmd::CloseAllocation { bump } => {
+            instructions::close_allocation(program_id, accounts, bump)?;
         }
     }
 
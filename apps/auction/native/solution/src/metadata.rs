@@ -0,0 +1,128 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey,
+    pubkey::Pubkey,
+};
+
+// Metaplex Token Metadata account.
+// Fields are read in the Metaplex layout up to collection, the fields after
+// it are not used by the auction.
+// The account must be owned by the Metaplex program, otherwise a seller
+// could write metadata with any collection and creators. Tests don't run
+// the Metaplex program, they write this local form of the account directly.
+
+pub const METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+pub const SEED_PREFIX: &[u8; 8] = b"metadata";
+pub const MAX_ROYALTY_BPS: u16 = 10000;
+// Key::MetadataV1
+pub const KEY_METADATA_V1: u8 = 4;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Creator {
+    pub address: Pubkey,
+    // Creator signed the metadata, unverified creators are not paid
+    pub verified: bool,
+    // Percent of royalties, shares of all creators add up to 100
+    pub share: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    pub verified: bool,
+    // Mint of the collection NFT
+    pub key: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Data {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    // Royalties paid to creators, 100 = 1%
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Metadata {
+    // KEY_METADATA_V1
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub data: Data,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    // TokenStandard, fieldless enum stored as u8
+    pub token_standard: Option<u8>,
+    pub collection: Option<Collection>,
+}
+
+impl Metadata {
+    pub fn creators(&self) -> &[Creator] {
+        self.data.creators.as_deref().unwrap_or(&[])
+    }
+}
+
+pub fn get_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+}
+
+// Read Metadata of mint, checking that metadata is the metadata PDA of mint
+pub fn get_metadata(
+    metadata: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<Metadata, ProgramError> {
+    if *metadata.owner != METADATA_PROGRAM_ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if *metadata.key != get_metadata_pda(mint).0 {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Account is longer than the fields read
+    let data = metadata.data.borrow();
+    let metadata = Metadata::deserialize(&mut &data[..])?;
+
+    if metadata.key != KEY_METADATA_V1 || metadata.mint != *mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if metadata.data.seller_fee_basis_points > MAX_ROYALTY_BPS {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let creators = metadata.creators();
+    let shares: u32 = creators.iter().map(|c| c.share as u32).sum();
+    if !creators.is_empty() && shares != 100 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(metadata)
+}
+
+// Royalty of each creator on buy_amt, rounded down.
+// Total royalty is capped at max, the amount left after the protocol fee.
+// Anyone can list a creator, so the share of unverified creators is 0 and
+// stays with the seller.
+pub fn get_royalties(metadata: &Metadata, buy_amt: u64, max: u64) -> Vec<u64> {
+    // buy_amt * seller_fee_basis_points / 10000 <= buy_amt
+    let royalty = (buy_amt as u128
+        * metadata.data.seller_fee_basis_points as u128
+        / 10000)
+        .min(max as u128);
+
+    metadata
+        .creators()
+        .iter()
+        .map(|c| {
+            if c.verified {
+                (royalty * c.share as u128 / 100) as u64
+            } else {
+                0
+            }
+        })
+        .collect()
+}
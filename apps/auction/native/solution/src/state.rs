@@ -1,17 +1,179 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum AuctionKind {
+    // Price decreases from start_price to end_price, first buyer wins
+    Dutch,
+    // Bidders escrow buy token, highest bid at end_time wins
+    English,
+    // Bidders escrow (quantity, max price) orders, after end_time all
+    // winning bids pay the same clearing price
+    Batch,
+    // Bidders commit hash of price with an escrow deposit until end_time,
+    // reveal during reveal_period, highest revealed bid wins
+    Sealed,
+}
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum PriceCurve {
+    // Price decreases linearly from start_price to end_price
+    Linear,
+    // Distance to end_price halves every curve_period seconds
+    Exponential,
+    // Price drops in equal steps every curve_period seconds,
+    // reaching end_price at the last step
+    Step,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Auction {
+    // seller is stored first so that clients can filter auctions by seller
+    pub seller: Pubkey,
+    pub auction_id: u64,
+    pub kind: AuctionKind,
+    // Dutch auction - how price decays from start_price to end_price
+    pub curve: PriceCurve,
+    // Half life for Exponential, step interval for Step, 0 for Linear
+    pub curve_period: u64,
+    // Merkle root of (buyer, cap) allowed to buy, [0; 32] if anyone can buy
+    pub allowlist_root: [u8; 32],
     pub mint_sell: Pubkey,
     pub mint_buy: Pubkey,
-    // Price of 1 sell token, 1e6 = 1 buy token
+    pub decimals_sell: u8,
+    pub decimals_buy: u8,
+    // Price of 1 whole sell token (10^decimals_sell units)
+    // in buy token units
+    // English, Batch and Sealed auction - bids must be >= end_price
     pub start_price: u64,
     pub end_price: u64,
     pub start_time: u64,
     pub end_time: u64,
+    // Amount of sell token locked at init
+    pub sell_amt: u64,
+    // Amount of sell token not sold yet
+    pub remaining: u64,
+    // English and Sealed auction - highest bid price and bidder
+    // highest_bidder = Pubkey::default() if there are no bids
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    // Sealed auction - bid PDA of highest_bidder, only this bid wins
+    pub highest_bid_pda: Pubkey,
+    // Sealed auction - bids are revealed from end_time to
+    // end_time + reveal_period
+    pub reveal_period: u64,
+    // Selling 1 NFT, buy pays royalties to creators in metadata
+    pub nft: bool,
+    // Collection the NFT is verified in, Pubkey::default() if not checked
+    pub collection: Pubkey,
+    // Batch and Sealed auction - number of bids not settled yet
+    pub num_bids: u64,
+    // Batch auction - bid PDA with the highest price, bids are linked by
+    // BatchBid.next from the highest price down
+    // Pubkey::default() if there are no bids
+    pub bid_head: Pubkey,
+    // Batch auction - price paid by all winning bids, 0 until cleared
+    pub clearing_price: u64,
+    // Batch auction - sell token shared pro rata by bids at clearing_price
+    // and total quantity of these bids
+    pub tie_supply: u64,
+    pub tie_demand: u128,
+    // Batch auction - batch_clear walks the bids over as many transactions
+    // as needed. Next bid to count, total quantity of bids above
+    // clear_price and price of the bids being counted, 0 until started.
+    // tie_demand sums the quantity of bids at clear_price.
+    pub clear_next: Pubkey,
+    pub clear_above: u64,
+    pub clear_price: u64,
 }
 
 impl Auction {
     pub const SEED_PREFIX: &'static [u8; 7] = b"auction";
+    // 32 + 8 + 1 + 1 + 8 + 32 + 32 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+    // + 32 + 32 + 8 + 1 + 32 + 8 + 32 + 8 + 8 + 16 + 32 + 8 + 8
+    pub const SPACE: u64 = 429;
+    // Share of auction PDA rent paid to whoever reclaims an expired auction
+    pub const RECLAIM_BOUNTY_BPS: u64 = 1000;
+    // Sealed auction - max reveal_period, 30 days
+    pub const MAX_REVEAL_PERIOD: u64 = 30 * 24 * 3600;
+    // Sealed auction - share of deposit paid to seller by unrevealed bids
+    pub const SEALED_PENALTY_BPS: u64 = 1000;
+    // Sentinel mint_buy of auctions paid in lamports, the System Program
+    pub const SOL_MINT: Pubkey = Pubkey::new_from_array([0; 32]);
+    // 1 SOL = 10^9 lamports
+    pub const SOL_DECIMALS: u8 = 9;
+}
+
+// Amount bought by a buyer with a capped allowlist entry
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Allocation {
+    pub bought: u64,
+}
+
+impl Allocation {
+    pub const SEED_PREFIX: &'static [u8; 10] = b"allocation";
+    pub const SPACE: u64 = 8;
+}
+
+// Batch auction order, one per bidder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct BatchBid {
+    pub bidder: Pubkey,
+    // Amount of sell token to buy
+    pub quantity: u64,
+    // Max price of 1 whole sell token in buy token units
+    pub price: u64,
+    // Next bid PDA, price <= this price, Pubkey::default() if last
+    pub next: Pubkey,
+    // Batch bid PDA bump
+    pub bump: u8,
+}
+
+impl BatchBid {
+    pub const SEED_PREFIX: &'static [u8; 9] = b"batch_bid";
+    // 32 + 8 + 8 + 32 + 1
+    pub const SPACE: u64 = 81;
+}
+
+// Sealed auction bid, one per bidder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SealedBid {
+    pub bidder: Pubkey,
+    // hash(bidder, price, salt)
+    pub commitment: [u8; 32],
+    // Amount of buy token escrowed, must cover the revealed price
+    pub deposit: u64,
+    // Revealed price, 0 until revealed
+    pub price: u64,
+    pub revealed: bool,
+    // Sealed bid PDA bump
+    pub bump: u8,
+}
+
+impl SealedBid {
+    pub const SEED_PREFIX: &'static [u8; 10] = b"sealed_bid";
+    // 32 + 32 + 8 + 8 + 1 + 1
+    pub const SPACE: u64 = 82;
+}
+
+// Global protocol config
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Config {
+    // Can change the fee and withdraw accrued fees
+    pub admin: Pubkey,
+    // Protocol fee taken from buy token paid by buyers, 100 = 1%
+    pub fee_bps: u16,
+    // Config PDA bump
+    pub bump: u8,
+}
+
+impl Config {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"config";
+    // 32 + 2 + 1
+    pub const SPACE: u64 = 35;
+    pub const MAX_FEE_BPS: u16 = 1000;
 }
@@ -1,22 +1,39 @@
-use litesvm::LiteSVM;
+use borsh::BorshDeserialize;
+use litesvm::{LiteSVM, types::TransactionResult};
 use litesvm_token::{
     CreateAssociatedTokenAccount, CreateMint, MintTo, get_spl_account,
     spl_token::state::Account as TokenAccount,
 };
 use solana_address::Address;
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
     pubkey::Pubkey,
     signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
 };
+use solana_sdk_ids::bpf_loader_upgradeable;
 use spl_associated_token_account_interface::address::get_associated_token_address;
 
-use auction::{Cmd, state::Auction};
+use auction::{
+    Cmd,
+    allowlist::AllowlistProof,
+    error::AuctionError,
+    instructions::InitParams,
+    metadata::{
+        Collection, Creator, Data, KEY_METADATA_V1, METADATA_PROGRAM_ID,
+        Metadata, get_metadata_pda,
+    },
+    state::{
+        Allocation, Auction, AuctionKind, BatchBid, Config, PriceCurve,
+        SealedBid,
+    },
+};
 
-pub fn create_mint(svm: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+pub fn create_mint(svm: &mut LiteSVM, payer: &Keypair, decimals: u8) -> Pubkey {
     CreateMint::new(svm, payer)
         .authority(&payer.pubkey())
-        .decimals(1e6 as u8)
+        .decimals(decimals)
         .send()
         .unwrap()
 }
@@ -29,6 +46,15 @@ pub fn get_ata(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
     Pubkey::from(ata_addr.to_bytes())
 }
 
+// Holder of buy token of owner, owner itself if paid in SOL
+pub fn get_buy_account(mint_buy: &Pubkey, owner: &Pubkey) -> Pubkey {
+    if *mint_buy == Auction::SOL_MINT {
+        *owner
+    } else {
+        get_ata(mint_buy, owner)
+    }
+}
+
 pub fn create_ata(
     svm: &mut LiteSVM,
     payer: &Keypair,
@@ -59,6 +85,110 @@ pub fn get_token_balance(svm: &LiteSVM, account: &Pubkey) -> u64 {
     token_account.amount
 }
 
+// Check that transaction failed with err
+pub fn assert_auction_error(res: TransactionResult, err: AuctionError) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => {
+            assert_eq!(e, InstructionError::Custom(err as u32))
+        }
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+pub fn assert_instruction_error(res: TransactionResult, err: InstructionError) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => assert_eq!(e, err),
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+// Write local form of NFT metadata for mint
+pub fn create_metadata(
+    svm: &mut LiteSVM,
+    mint: &Pubkey,
+    seller_fee_basis_points: u16,
+    creators: &[(Pubkey, u8)],
+    collection: Option<Pubkey>,
+) -> Pubkey {
+    let metadata = Metadata {
+        key: KEY_METADATA_V1,
+        update_authority: Pubkey::new_unique(),
+        mint: *mint,
+        data: Data {
+            name: "NFT".to_string(),
+            symbol: "NFT".to_string(),
+            uri: String::new(),
+            seller_fee_basis_points,
+            creators: Some(
+                creators
+                    .iter()
+                    .map(|(address, share)| Creator {
+                        address: *address,
+                        verified: true,
+                        share: *share,
+                    })
+                    .collect(),
+            ),
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: collection.map(|key| Collection {
+            verified: true,
+            key,
+        }),
+    };
+
+    // Metaplex allocates MAX_METADATA_LEN bytes, fields after collection
+    // are left zeroed
+    let mut data = borsh::to_vec(&metadata).unwrap();
+    data.resize(679, 0);
+    let (metadata_pda, _) = get_metadata_pda(mint);
+
+    svm.set_account(
+        metadata_pda,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: METADATA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    metadata_pda
+}
+
+// Metadata and (creator, creator_buy_ata) accounts to append to an NFT buy
+pub fn get_royalty_accounts(
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    creators: &[Pubkey],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![AccountMeta {
+        pubkey: get_metadata_pda(mint_sell).0,
+        is_signer: false,
+        is_writable: true,
+    }];
+
+    for creator in creators {
+        accounts.push(AccountMeta {
+            pubkey: *creator,
+            is_signer: false,
+            is_writable: true,
+        });
+        accounts.push(AccountMeta {
+            pubkey: get_ata(mint_buy, creator),
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    accounts
+}
+
 pub fn create_init_ix(
     program_id: Pubkey,
     start_price: u64,
@@ -66,6 +196,14 @@ pub fn create_init_ix(
     start_time: u64,
     end_time: u64,
     sell_amt: u64,
+    kind: AuctionKind,
+    curve: PriceCurve,
+    curve_period: u64,
+    allowlist_root: Option<[u8; 32]>,
+    reveal_period: u64,
+    nft: bool,
+    collection: Option<Pubkey>,
+    auction_id: u64,
     bump: u8,
     seller: Pubkey,
     mint_sell: Pubkey,
@@ -74,15 +212,224 @@ pub fn create_init_ix(
     auction_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Init {
+    let cmd = Cmd::Init(InitParams {
         start_price,
         end_price,
         start_time,
         end_time,
         sell_amt,
+        kind,
+        curve,
+        curve_period,
+        allowlist_root,
+        reveal_period,
+        nft,
+        collection,
+        auction_id,
+        bump,
+    });
+
+    let mut accounts = vec![
+        AccountMeta {
+            pubkey: seller,
+            is_signer: true,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_sell,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_buy,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: auction_pda,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: auction_sell_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: seller_sell_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: Pubkey::from(
+                spl_associated_token_account_interface::program::ID.to_bytes(),
+            ),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: solana_sdk::system_program::id(),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: solana_sdk::sysvar::rent::id(),
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    // Only needed for an NFT
+    if nft {
+        accounts.push(AccountMeta {
+            pubkey: get_metadata_pda(&mint_sell).0,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    Instruction::new_with_borsh(program_id, &cmd, accounts)
+}
+
+pub fn create_buy_ix(
+    program_id: Pubkey,
+    amount: u64,
+    max_price: u64,
+    allowlist: Option<AllowlistProof>,
+    auction_id: u64,
+    bump: u8,
+    buyer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    buyer_sell_ata: Pubkey,
+    buyer_buy_ata: Pubkey,
+    seller_buy_ata: Pubkey,
+    allocation_pda: Option<Pubkey>,
+) -> Instruction {
+    let (config_pda, _) = get_config_pda(&program_id);
+    let fee_vault = get_buy_account(&mint_buy, &config_pda);
+
+    let cmd = Cmd::Buy {
+        amount,
+        max_price,
+        allowlist,
+        auction_id,
         bump,
     };
 
+    let mut accounts = vec![
+        AccountMeta {
+            pubkey: buyer,
+            is_signer: true,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: seller,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_sell,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_buy,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: auction_pda,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: auction_sell_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: buyer_sell_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: buyer_buy_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: seller_buy_ata,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: config_pda,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: fee_vault,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: Pubkey::from(
+                spl_associated_token_account_interface::program::ID.to_bytes(),
+            ),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: solana_sdk::system_program::id(),
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: solana_sdk::sysvar::rent::id(),
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    // Only needed for an allowlist entry with a cap
+    if let Some(allocation_pda) = allocation_pda {
+        accounts.push(AccountMeta {
+            pubkey: allocation_pda,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    Instruction::new_with_borsh(program_id, &cmd, accounts)
+}
+
+pub fn create_cancel_ix(
+    program_id: Pubkey,
+    auction_id: u64,
+    bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Cancel { auction_id, bump };
+
     Instruction::new_with_borsh(
         program_id,
         &cmd,
@@ -123,49 +470,78 @@ pub fn create_init_ix(
                 is_writable: true,
             },
             AccountMeta {
-                pubkey: Pubkey::from(
-                    spl_associated_token_account_interface::program::ID
-                        .to_bytes(),
-                ),
+                pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
                 is_writable: true,
             },
+        ],
+    )
+}
+
+pub fn create_close_allocation_ix(
+    program_id: Pubkey,
+    bump: u8,
+    buyer: Pubkey,
+    auction_pda: Pubkey,
+    allocation_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::CloseAllocation { bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: buyer,
+                is_signer: true,
+                is_writable: true,
+            },
             AccountMeta {
-                pubkey: solana_sdk::system_program::id(),
+                pubkey: auction_pda,
                 is_signer: false,
-                is_writable: true,
+                is_writable: false,
             },
             AccountMeta {
-                pubkey: solana_sdk::sysvar::rent::id(),
+                pubkey: allocation_pda,
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
         ],
     )
 }
 
-pub fn create_buy_ix(
+pub fn create_bid_ix(
     program_id: Pubkey,
-    max_price: u64,
+    price: u64,
+    auction_id: u64,
     bump: u8,
-    buyer: Pubkey,
+    bidder: Pubkey,
     seller: Pubkey,
     mint_sell: Pubkey,
     mint_buy: Pubkey,
     auction_pda: Pubkey,
-    auction_sell_ata: Pubkey,
-    buyer_sell_ata: Pubkey,
-    buyer_buy_ata: Pubkey,
-    seller_buy_ata: Pubkey,
+    auction_buy_ata: Pubkey,
+    bidder_buy_ata: Pubkey,
+    prev_bidder: Pubkey,
+    prev_bidder_buy_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Buy { max_price, bump };
+    let cmd = Cmd::Bid {
+        price,
+        auction_id,
+        bump,
+    };
 
     Instruction::new_with_borsh(
         program_id,
         &cmd,
         vec![
             AccountMeta {
-                pubkey: buyer,
+                pubkey: bidder,
                 is_signer: true,
                 is_writable: true,
             },
@@ -190,22 +566,22 @@ pub fn create_buy_ix(
                 is_writable: true,
             },
             AccountMeta {
-                pubkey: auction_sell_ata,
+                pubkey: auction_buy_ata,
                 is_signer: false,
                 is_writable: true,
             },
             AccountMeta {
-                pubkey: buyer_sell_ata,
+                pubkey: bidder_buy_ata,
                 is_signer: false,
                 is_writable: true,
             },
             AccountMeta {
-                pubkey: buyer_buy_ata,
+                pubkey: prev_bidder,
                 is_signer: false,
                 is_writable: true,
             },
             AccountMeta {
-                pubkey: seller_buy_ata,
+                pubkey: prev_bidder_buy_ata,
                 is_signer: false,
                 is_writable: true,
             },
@@ -214,36 +590,63 @@ pub fn create_buy_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     )
 }
 
-pub fn create_cancel_ix(
+pub fn create_settle_ix(
     program_id: Pubkey,
+    auction_id: u64,
     bump: u8,
+    payer: Pubkey,
     seller: Pubkey,
     mint_sell: Pubkey,
     mint_buy: Pubkey,
     auction_pda: Pubkey,
     auction_sell_ata: Pubkey,
+    auction_buy_ata: Pubkey,
+    winner: Pubkey,
+    winner_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
+    seller_buy_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Cancel { bump };
+    let (config_pda, _) = get_config_pda(&program_id);
+    let fee_vault = get_ata(&mint_buy, &config_pda);
+
+    let cmd = Cmd::Settle { auction_id, bump };
 
     Instruction::new_with_borsh(
         program_id,
         &cmd,
         vec![
             AccountMeta {
-                pubkey: seller,
+                pubkey: payer,
                 is_signer: true,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: mint_sell,
                 is_signer: false,
@@ -264,21 +667,64 @@ pub fn create_cancel_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: auction_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: winner,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: winner_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: seller_sell_ata,
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: seller_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: fee_vault,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     )
 }
@@ -295,37 +741,1062 @@ pub struct Test {
     pub buyer_sell_ata: Pubkey,
     pub buyer_buy_ata: Pubkey,
     pub auction_pda: Pubkey,
+    pub auction_id: u64,
     pub auction_bump: u8,
     pub auction_sell_ata: Pubkey,
 }
 
-pub fn setup(svm: &mut LiteSVM) -> Test {
-    let payer = Keypair::new();
-    let seller = Keypair::new();
-    let buyer = Keypair::new();
-    let program_keypair = Keypair::new();
-    let program_id = program_keypair.pubkey();
-    svm.add_program_from_file(program_id, "target/deploy/auction.so")
-        .unwrap();
-
-    // Airdrop
-    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
-    svm.airdrop(&seller.pubkey(), 1_000_000_000).unwrap();
-    svm.airdrop(&buyer.pubkey(), 1_000_000_000).unwrap();
+pub fn create_reclaim_ix(
+    program_id: Pubkey,
+    auction_id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Reclaim { auction_id, bump };
 
-    // Mints
-    let mint_sell = create_mint(svm, &payer);
-    let mint_buy = create_mint(svm, &payer);
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
 
-    // Auction PDA
-    let (auction_pda, auction_bump) = Pubkey::find_program_address(
-        &[
+pub fn create_update_ix(
+    program_id: Pubkey,
+    end_price: Option<u64>,
+    end_time: Option<u64>,
+    top_up: Option<u64>,
+    auction_id: u64,
+    bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Update {
+        end_price,
+        end_time,
+        top_up,
+        auction_id,
+        bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_batch_bid_ix(
+    program_id: Pubkey,
+    quantity: u64,
+    price: u64,
+    auction_id: u64,
+    bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    // See get_batch_bid_position
+    prev_pda: Pubkey,
+    next_pda: Pubkey,
+) -> Instruction {
+    let (bid_pda, bid_bump) =
+        get_batch_bid_pda(&program_id, &auction_pda, &bidder);
+
+    let cmd = Cmd::BatchBid {
+        quantity,
+        price,
+        auction_id,
+        bump,
+        bid_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: prev_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: next_pda,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_batch_clear_ix(
+    program_id: Pubkey,
+    auction_id: u64,
+    bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    bid_pdas: &[Pubkey],
+) -> Instruction {
+    let cmd = Cmd::BatchClear { auction_id, bump };
+
+    let mut accounts = vec![
+        AccountMeta {
+            pubkey: seller,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_sell,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: mint_buy,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: auction_pda,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    // Every bid of the auction
+    for bid_pda in bid_pdas {
+        accounts.push(AccountMeta {
+            pubkey: *bid_pda,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    Instruction::new_with_borsh(program_id, &cmd, accounts)
+}
+
+pub fn create_batch_settle_ix(
+    program_id: Pubkey,
+    auction_id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    bidder: Pubkey,
+) -> Instruction {
+    let (bid_pda, _) = get_batch_bid_pda(&program_id, &auction_pda, &bidder);
+    let (config_pda, _) = get_config_pda(&program_id);
+
+    let cmd = Cmd::BatchSettle { auction_id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &config_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_sealed_commit_ix(
+    program_id: Pubkey,
+    commitment: [u8; 32],
+    deposit: u64,
+    auction_id: u64,
+    bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let (bid_pda, bid_bump) =
+        get_sealed_bid_pda(&program_id, &auction_pda, &bidder);
+
+    let cmd = Cmd::SealedCommit {
+        commitment,
+        deposit,
+        auction_id,
+        bump,
+        bid_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_sealed_reveal_ix(
+    program_id: Pubkey,
+    price: u64,
+    salt: [u8; 32],
+    auction_id: u64,
+    bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let (bid_pda, _) = get_sealed_bid_pda(&program_id, &auction_pda, &bidder);
+
+    let cmd = Cmd::SealedReveal {
+        price,
+        salt,
+        auction_id,
+        bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_sealed_settle_ix(
+    program_id: Pubkey,
+    auction_id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    bidder: Pubkey,
+) -> Instruction {
+    let (bid_pda, _) = get_sealed_bid_pda(&program_id, &auction_pda, &bidder);
+    let (config_pda, _) = get_config_pda(&program_id);
+
+    let cmd = Cmd::SealedSettle { auction_id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &config_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_get_price_ix(
+    program_id: Pubkey,
+    amount: u64,
+    auction_id: u64,
+    bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::GetPrice {
+        amount,
+        auction_id,
+        bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+    )
+}
+
+pub fn create_init_config_ix(
+    program_id: Pubkey,
+    fee_bps: u16,
+    bump: u8,
+    admin: Pubkey,
+) -> Instruction {
+    let (config_pda, _) = get_config_pda(&program_id);
+
+    let cmd = Cmd::InitConfig { fee_bps, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: admin,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_program_data(&program_id),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_set_fee_ix(
+    program_id: Pubkey,
+    fee_bps: u16,
+    admin: Pubkey,
+) -> Instruction {
+    let (config_pda, _) = get_config_pda(&program_id);
+
+    let cmd = Cmd::SetFee { fee_bps };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: admin,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_withdraw_fees_ix(
+    program_id: Pubkey,
+    amount: u64,
+    admin: Pubkey,
+    mint_buy: Pubkey,
+    dst: Pubkey,
+) -> Instruction {
+    let (config_pda, _) = get_config_pda(&program_id);
+    let fee_vault = get_buy_account(&mint_buy, &config_pda);
+
+    let cmd = Cmd::WithdrawFees { amount };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: admin,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: fee_vault,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: dst,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn get_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::SEED_PREFIX], program_id)
+}
+
+pub fn get_program_data(program_id: &Pubkey) -> Pubkey {
+    let (program_data, _) = Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    program_data
+}
+
+// Program data account of an upgradeable program with upgrade authority.
+// add_program doesn't deploy with the upgradeable loader.
+pub fn set_upgrade_authority(
+    svm: &mut LiteSVM,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) {
+    // UpgradeableLoaderState::ProgramData { slot: 0, authority }
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+
+    svm.set_account(
+        get_program_data(program_id),
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+pub fn get_auction_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    auction_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
             Auction::SEED_PREFIX,
-            seller.pubkey().as_ref(),
+            seller.as_ref(),
             mint_sell.as_ref(),
             mint_buy.as_ref(),
+            &auction_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_allocation_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    buyer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Allocation::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+// PDA of seeds with a valid bump other than the canonical bump
+pub fn get_non_canonical_pda(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+) -> (Pubkey, u8) {
+    let (_, canonical_bump) = Pubkey::find_program_address(seeds, program_id);
+    (0..canonical_bump)
+        .rev()
+        .find_map(|bump| {
+            let mut seeds = seeds.to_vec();
+            let bump = [bump];
+            seeds.push(&bump);
+            Pubkey::create_program_address(&seeds, program_id)
+                .ok()
+                .map(|pda| (pda, bump[0]))
+        })
+        .unwrap()
+}
+
+pub fn get_batch_bid_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BatchBid::SEED_PREFIX, auction_pda.as_ref(), bidder.as_ref()],
+        program_id,
+    )
+}
+
+// Bids of a batch auction from the highest price down
+pub fn get_batch_bids(svm: &LiteSVM, auction_pda: &Pubkey) -> Vec<Pubkey> {
+    let data = svm.get_account(auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+
+    let mut bids = vec![];
+    let mut next = auction.bid_head;
+    while next != Pubkey::default() {
+        bids.push(next);
+        let data = svm.get_account(&next).unwrap().data;
+        next = BatchBid::try_from_slice(&data).unwrap().next;
+    }
+    bids
+}
+
+// (prev_pda, next_pda) to insert a batch bid at price, after bids with
+// price >= this price
+pub fn get_batch_bid_position(
+    svm: &LiteSVM,
+    auction_pda: &Pubkey,
+    price: u64,
+) -> (Pubkey, Pubkey) {
+    let mut prev = *auction_pda;
+    for bid_pda in get_batch_bids(svm, auction_pda) {
+        let data = svm.get_account(&bid_pda).unwrap().data;
+        if BatchBid::try_from_slice(&data).unwrap().price < price {
+            return (prev, bid_pda);
+        }
+        prev = bid_pda;
+    }
+    (prev, Pubkey::default())
+}
+
+pub fn get_sealed_bid_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SealedBid::SEED_PREFIX,
+            auction_pda.as_ref(),
+            bidder.as_ref(),
         ],
+        program_id,
+    )
+}
+
+pub fn setup(svm: &mut LiteSVM) -> Test {
+    setup_with_decimals(svm, 6, 6)
+}
+
+pub fn setup_with_decimals(
+    svm: &mut LiteSVM,
+    decimals_sell: u8,
+    decimals_buy: u8,
+) -> Test {
+    let payer = Keypair::new();
+    let seller = Keypair::new();
+    let buyer = Keypair::new();
+    let program_keypair = Keypair::new();
+    let program_id = program_keypair.pubkey();
+    svm.add_program_from_file(program_id, "target/deploy/auction.so")
+        .unwrap();
+
+    // Airdrop
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&seller.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&buyer.pubkey(), 1_000_000_000).unwrap();
+
+    // Config, payer is upgrade authority and admin, no protocol fee
+    set_upgrade_authority(svm, &program_id, &payer.pubkey());
+    let (_, config_bump) = get_config_pda(&program_id);
+    let ix = create_init_config_ix(program_id, 0, config_bump, payer.pubkey());
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // Mints
+    let mint_sell = create_mint(svm, &payer, decimals_sell);
+    let mint_buy = create_mint(svm, &payer, decimals_buy);
+
+    // Auction PDA
+    let auction_id = 0;
+    let (auction_pda, auction_bump) = get_auction_pda(
         &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        auction_id,
     );
 
     // ATA
@@ -335,9 +1806,11 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
     let buyer_buy_ata = create_ata(svm, &payer, &buyer.pubkey(), &mint_buy);
     let auction_sell_ata = get_ata(&mint_sell, &auction_pda);
 
-    // Mint to
-    mint_to(svm, &payer, &mint_sell, &seller_sell_ata, 1e9 as u64);
-    mint_to(svm, &payer, &mint_buy, &buyer_buy_ata, 1e9 as u64);
+    // Mint to, 1000 whole tokens
+    let amt_sell = 1000 * 10u64.pow(decimals_sell as u32);
+    let amt_buy = 1000 * 10u64.pow(decimals_buy as u32);
+    mint_to(svm, &payer, &mint_sell, &seller_sell_ata, amt_sell);
+    mint_to(svm, &payer, &mint_buy, &buyer_buy_ata, amt_buy);
 
     Test {
         program_id,
@@ -351,6 +1824,7 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
         buyer_sell_ata,
         buyer_buy_ata,
         auction_pda,
+        auction_id,
         auction_bump,
         auction_sell_ata,
     }
@@ -1,14 +1,42 @@
 use borsh::BorshDeserialize;
 use litesvm::LiteSVM;
 use solana_program::sysvar::clock::Clock;
-use solana_sdk::{signature::Signer, transaction::Transaction};
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
 
-use auction::{Cmd, state::Auction};
+use auction::{
+    Cmd,
+    allowlist::{self, AllowlistProof, NO_CAP},
+    error::AuctionError,
+    history,
+    instructions::{Quote, lib::get_commitment},
+    metadata::{
+        self, Creator, Data, KEY_METADATA_V1, MAX_ROYALTY_BPS, Metadata,
+    },
+    state::{
+        Allocation, Auction, AuctionKind, BatchBid, Config, PriceCurve,
+        SealedBid,
+    },
+};
 
 mod helper;
 use helper::{
-    Test, create_buy_ix, create_cancel_ix, create_init_ix, get_token_balance,
-    setup,
+    Test, assert_auction_error, assert_instruction_error, create_ata,
+    create_batch_bid_ix, create_batch_clear_ix, create_batch_settle_ix,
+    create_bid_ix, create_buy_ix, create_cancel_ix, create_close_allocation_ix,
+    create_get_price_ix, create_init_config_ix, create_init_ix,
+    create_metadata, create_mint, create_reclaim_ix, create_sealed_commit_ix,
+    create_sealed_reveal_ix, create_sealed_settle_ix, create_set_fee_ix,
+    create_settle_ix, create_update_ix, create_withdraw_fees_ix,
+    get_allocation_pda, get_ata, get_auction_pda, get_batch_bid_pda,
+    get_batch_bid_position, get_batch_bids, get_config_pda,
+    get_non_canonical_pda, get_royalty_accounts, get_sealed_bid_pda,
+    get_token_balance, mint_to, setup, setup_with_decimals,
 };
 
 #[test]
@@ -26,6 +54,7 @@ fn test_init() {
         buyer_sell_ata,
         buyer_buy_ata,
         auction_pda,
+        auction_id,
         auction_bump,
         auction_sell_ata,
     } = setup(&mut svm);
@@ -45,6 +74,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -70,6 +107,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -85,7 +130,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidTokenAccount);
 
     // Check seller_sell_ata
     let ix = create_init_ix(
@@ -95,6 +140,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -110,7 +163,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidTokenAccount);
 
     // Check sell token != buy token
     let ix = create_init_ix(
@@ -120,6 +173,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -135,7 +196,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidMints);
 
     // Check start_price >= end_price
     let ix = create_init_ix(
@@ -145,6 +206,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -160,7 +229,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidPrices);
 
     // Check now <= start_time < end_time
     let ix = create_init_ix(
@@ -170,6 +239,14 @@ fn test_init() {
         end_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -185,7 +262,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidEndTime);
 
     // Check sell_amt > 0
     let ix = create_init_ix(
@@ -195,6 +272,14 @@ fn test_init() {
         start_time,
         end_time,
         0,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -210,7 +295,7 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_auction_error(res, AuctionError::InvalidSellAmount);
 
     // Init
     let ix = create_init_ix(
@@ -220,6 +305,14 @@ fn test_init() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -266,6 +359,7 @@ fn test_buy() {
         buyer_sell_ata,
         buyer_buy_ata,
         auction_pda,
+        auction_id,
         auction_bump,
         auction_sell_ata,
     } = setup(&mut svm);
@@ -285,6 +379,14 @@ fn test_buy() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -302,16 +404,207 @@ fn test_buy() {
     ));
     assert!(res.is_ok());
 
+    let max_price = start_price - 1;
+
+    let buy_ix = || {
+        create_buy_ix(
+            program_id,
+            sell_amt,
+            max_price,
+            None,
+            auction_id,
+            auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+            None,
+        )
+    };
+
+    // Auction not started
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix()],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotStarted);
+
+    // Auction ended
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix()],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionEnded);
+
+    // Current price > max_price
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix()],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::MaxPrice);
+
     // Buy
     let mut clock = svm.get_sysvar::<Clock>();
     clock.unix_timestamp = (start_time + 2) as i64;
     svm.set_sysvar(&clock);
+    svm.expire_blockhash();
 
-    let max_price = start_price - 1;
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix()],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert!(get_token_balance(&svm, &seller_buy_ata) > 0);
+}
+
+#[test]
+fn test_get_price() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let amount = sell_amt / 4;
+    let get_price_ix = |amount| {
+        create_get_price_ix(
+            program_id,
+            amount,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+        )
+    };
+
+    // Auction not started
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[get_price_ix(amount)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotStarted);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    // Amount > remaining
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[get_price_ix(sell_amt + 1)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAmount);
+
+    // Get price
+    let res = svm
+        .send_transaction(Transaction::new_signed_with_payer(
+            &[get_price_ix(amount)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .unwrap();
 
+    assert_eq!(res.return_data.program_id, program_id);
+    let quote = Quote::try_from_slice(&res.return_data.data).unwrap();
+
+    // 2 seconds into 10, price dropped by 0.1
+    assert_eq!(quote.price, (1.9 * 1e6) as u64);
+    assert_eq!(quote.buy_amt, 19 * amount / 10);
+
+    // Buy charges the quoted amount at the quoted price
     let ix = create_buy_ix(
         program_id,
-        max_price,
+        amount,
+        quote.price,
+        None,
+        auction_id,
         auction_bump,
         buyer.pubkey(),
         seller.pubkey(),
@@ -322,8 +615,11 @@ fn test_buy() {
         buyer_sell_ata,
         buyer_buy_ata,
         seller_buy_ata,
+        None,
     );
 
+    let buyer_buy_bal = get_token_balance(&svm, &buyer_buy_ata);
+
     let res = svm.send_transaction(Transaction::new_signed_with_payer(
         &[ix],
         Some(&buyer.pubkey()),
@@ -332,14 +628,16 @@ fn test_buy() {
     ));
     assert!(res.is_ok());
 
-    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
-    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
-    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
-    assert!(get_token_balance(&svm, &seller_buy_ata) > 0);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_buy_ata),
+        buyer_buy_bal - quote.buy_amt
+    );
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), quote.buy_amt);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), amount);
 }
 
 #[test]
-fn test_cancel() {
+fn test_buy_partial() {
     let mut svm = LiteSVM::new();
     let Test {
         program_id,
@@ -353,6 +651,7 @@ fn test_cancel() {
         buyer_sell_ata,
         buyer_buy_ata,
         auction_pda,
+        auction_id,
         auction_bump,
         auction_sell_ata,
     } = setup(&mut svm);
@@ -372,6 +671,14 @@ fn test_cancel() {
         start_time,
         end_time,
         sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -389,9 +696,175 @@ fn test_cancel() {
     ));
     assert!(res.is_ok());
 
-    // Cancel
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    let buy_ix = |amount: u64| {
+        create_buy_ix(
+            program_id,
+            amount,
+            start_price,
+            None,
+            auction_id,
+            auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+            None,
+        )
+    };
+
+    // Buy 0
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(0)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAmount);
+
+    // Buy more than remaining
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(sell_amt + 1)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAmount);
+
+    // Buy 1/4
+    let amt_0 = sell_amt / 4;
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(amt_0)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.sell_amt, sell_amt);
+    assert_eq!(auction.remaining, sell_amt - amt_0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), amt_0);
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt - amt_0);
+
+    // Anyone sends sell token to auction_sell_ata
+    mint_to(&mut svm, &payer, &mint_sell, &auction_sell_ata, 1);
+
+    // Buy the rest
+    let amt_1 = sell_amt - amt_0;
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(amt_1)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Sold out, auction is closed, extra sell token goes to buyer
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt + 1);
+}
+
+#[test]
+fn test_concurrent_auctions() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    // Second auction of the same seller and mints
+    let auction_id_1 = auction_id + 1;
+    let (auction_pda_1, auction_bump_1) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        auction_id_1,
+    );
+    let auction_sell_ata_1 = get_ata(&mint_sell, &auction_pda_1);
+    assert_ne!(auction_pda, auction_pda_1);
+
+    for (id, bump, pda, ata) in [
+        (auction_id, auction_bump, auction_pda, auction_sell_ata),
+        (
+            auction_id_1,
+            auction_bump_1,
+            auction_pda_1,
+            auction_sell_ata_1,
+        ),
+    ] {
+        let ix = create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            AuctionKind::Dutch,
+            PriceCurve::Linear,
+            0,
+            None,
+            0,
+            false,
+            None,
+            id,
+            bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            pda,
+            ata,
+            seller_sell_ata,
+        );
+
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+
+        let data = svm.get_account(&pda).unwrap().data;
+        let auction = Auction::try_from_slice(&data).unwrap();
+        assert_eq!(auction.seller, seller.pubkey());
+        assert_eq!(auction.auction_id, id);
+    }
+
+    // auction_id must match the PDA
     let ix = create_cancel_ix(
         program_id,
+        auction_id_1,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -401,6 +874,27 @@ fn test_cancel() {
         seller_sell_ata,
     );
 
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Cancel second auction, first stays open
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id_1,
+        auction_bump_1,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda_1,
+        auction_sell_ata_1,
+        seller_sell_ata,
+    );
+
     let res = svm.send_transaction(Transaction::new_signed_with_payer(
         &[ix],
         Some(&seller.pubkey()),
@@ -409,7 +903,3327 @@ fn test_cancel() {
     ));
     assert!(res.is_ok());
 
-    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
-    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
-    assert_eq!(get_token_balance(&svm, &seller_sell_ata), 1e9 as u64);
+    assert_eq!(svm.get_balance(&auction_pda_1).unwrap_or(0), 0);
+    assert!(svm.get_balance(&auction_pda).unwrap() > 0);
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+}
+
+#[test]
+fn test_buy_decimals() {
+    // (decimals_sell, decimals_buy)
+    for (decimals_sell, decimals_buy) in [(0, 9), (6, 6), (9, 0)] {
+        let mut svm = LiteSVM::new();
+        let Test {
+            program_id,
+            payer,
+            seller,
+            buyer,
+            mint_sell,
+            mint_buy,
+            seller_sell_ata,
+            seller_buy_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            auction_pda,
+            auction_id,
+            auction_bump,
+            auction_sell_ata,
+        } = setup_with_decimals(&mut svm, decimals_sell, decimals_buy);
+
+        let one_sell = 10u64.pow(decimals_sell as u32);
+        let one_buy = 10u64.pow(decimals_buy as u32);
+
+        // 2 whole buy tokens per whole sell token
+        let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+        let start_time = now + 1;
+        let end_time = start_time + 10;
+        let start_price = 2 * one_buy;
+        let end_price = one_buy;
+        let sell_amt = 10 * one_sell;
+
+        let ix = create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            AuctionKind::Dutch,
+            PriceCurve::Linear,
+            0,
+            None,
+            0,
+            false,
+            None,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        );
+
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+
+        let data = svm.get_account(&auction_pda).unwrap().data;
+        let auction = Auction::try_from_slice(&data).unwrap();
+        assert_eq!(auction.decimals_sell, decimals_sell);
+        assert_eq!(auction.decimals_buy, decimals_buy);
+
+        // Buy at start_price
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = start_time as i64;
+        svm.set_sysvar(&clock);
+
+        let buy_ix = |amount: u64| {
+            create_buy_ix(
+                program_id,
+                amount,
+                start_price,
+                None,
+                auction_id,
+                auction_bump,
+                buyer.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                buyer_sell_ata,
+                buyer_buy_ata,
+                seller_buy_ata,
+                None,
+            )
+        };
+
+        // 3 whole sell tokens cost 6 whole buy tokens
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[buy_ix(3 * one_sell)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+
+        assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 3 * one_sell);
+        assert_eq!(get_token_balance(&svm, &seller_buy_ata), 6 * one_buy);
+
+        // 1 sell token unit, buy amount is rounded up
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[buy_ix(1)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+
+        let buy_amt = (2 * one_buy).div_ceil(one_sell);
+        assert_eq!(
+            get_token_balance(&svm, &seller_buy_ata),
+            6 * one_buy + buy_amt
+        );
+    }
+}
+
+#[test]
+fn test_allowlist_proofs() {
+    for n in 1..=7 {
+        let leaves: Vec<[u8; 32]> = (0..n)
+            .map(|i| allowlist::get_leaf(&Pubkey::new_unique(), i))
+            .collect();
+        let tree = allowlist::build_tree(&leaves);
+        let root = allowlist::get_root(&tree);
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = allowlist::get_proof(&tree, i);
+            assert!(allowlist::verify(&root, leaf, &proof));
+
+            // Leaf not in the tree
+            let other = allowlist::get_leaf(&Pubkey::new_unique(), 0);
+            assert!(!allowlist::verify(&root, &other, &proof));
+        }
+    }
+}
+
+#[test]
+fn test_buy_allowlist() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    // Allowlist - buyer with a cap, payer without a cap
+    let cap = sell_amt / 4;
+    let leaves = vec![
+        allowlist::get_leaf(&Pubkey::new_unique(), NO_CAP),
+        allowlist::get_leaf(&buyer.pubkey(), cap),
+        allowlist::get_leaf(&payer.pubkey(), NO_CAP),
+    ];
+    let tree = allowlist::build_tree(&leaves);
+    let root = allowlist::get_root(&tree);
+
+    // Allowlist is only checked by buy of a Dutch auction
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::English,
+        PriceCurve::Linear,
+        0,
+        Some(root),
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAuctionKind);
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        Some(root),
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let (allocation_pda, allocation_bump) =
+        get_allocation_pda(&program_id, &auction_pda, &buyer.pubkey());
+
+    // Anyone sends lamports to allocation_pda before the first buy
+    svm.airdrop(&allocation_pda, 1).unwrap();
+
+    let close_allocation_ix = create_close_allocation_ix(
+        program_id,
+        allocation_bump,
+        buyer.pubkey(),
+        auction_pda,
+        allocation_pda,
+    );
+
+    let buy_ix = |amount: u64,
+                  allowlist: Option<AllowlistProof>,
+                  allocation_pda: Option<Pubkey>| {
+        create_buy_ix(
+            program_id,
+            amount,
+            start_price,
+            allowlist,
+            auction_id,
+            auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+            allocation_pda,
+        )
+    };
+
+    let proof = allowlist::get_proof(&tree, 1);
+    let valid = AllowlistProof {
+        cap,
+        proof: proof.clone(),
+        bump: allocation_bump,
+    };
+
+    let mut send = |ix| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+
+    // No proof
+    assert_auction_error(
+        send(buy_ix(1, None, None)),
+        AuctionError::InvalidAllowlist,
+    );
+
+    // Cap in the proof doesn't match the leaf
+    let no_cap = AllowlistProof {
+        cap: NO_CAP,
+        proof: proof.clone(),
+        bump: allocation_bump,
+    };
+    assert_auction_error(
+        send(buy_ix(1, Some(no_cap), None)),
+        AuctionError::NotAllowed,
+    );
+
+    // Proof of another leaf
+    let other = AllowlistProof {
+        cap,
+        proof: allowlist::get_proof(&tree, 2),
+        bump: allocation_bump,
+    };
+    assert_auction_error(
+        send(buy_ix(1, Some(other), Some(allocation_pda))),
+        AuctionError::NotAllowed,
+    );
+
+    // Missing allocation_pda
+    assert!(send(buy_ix(1, Some(valid.clone()), None)).is_err());
+
+    // Valid proof
+    let amt = cap / 2;
+    assert!(
+        send(buy_ix(amt, Some(valid.clone()), Some(allocation_pda))).is_ok()
+    );
+
+    // Over cap
+    assert_auction_error(
+        send(buy_ix(amt + 1, Some(valid.clone()), Some(allocation_pda))),
+        AuctionError::AllocationExceeded,
+    );
+
+    // Up to cap
+    assert!(send(buy_ix(amt, Some(valid), Some(allocation_pda))).is_ok());
+
+    // Another allocation with a non canonical bump
+    let (other_allocation_pda, other_bump) = get_non_canonical_pda(
+        &program_id,
+        &[
+            Allocation::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.pubkey().as_ref(),
+        ],
+    );
+    let non_canonical = AllowlistProof {
+        cap,
+        proof: proof.clone(),
+        bump: other_bump,
+    };
+    assert_instruction_error(
+        send(buy_ix(amt, Some(non_canonical), Some(other_allocation_pda))),
+        InstructionError::InvalidSeeds,
+    );
+
+    // Close allocation while the auction is open
+    assert!(send(close_allocation_ix.clone()).is_err());
+
+    let data = svm.get_account(&allocation_pda).unwrap().data;
+    let allocation = Allocation::try_from_slice(&data).unwrap();
+    assert_eq!(allocation.bought, 2 * amt);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 2 * amt);
+
+    // Cancel, then buyer gets allocation rent back
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let rent = svm.get_balance(&allocation_pda).unwrap();
+    let balance = svm.get_balance(&buyer.pubkey()).unwrap();
+
+    // Close by payer, any buyer only closes its own allocation
+    let ix = create_close_allocation_ix(
+        program_id,
+        allocation_bump,
+        payer.pubkey(),
+        auction_pda,
+        allocation_pda,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[close_allocation_ix],
+        Some(&payer.pubkey()),
+        &[&payer, &buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&allocation_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&buyer.pubkey()).unwrap(), balance + rent);
+}
+
+#[test]
+fn test_update() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    let mut txs = vec![res.unwrap().logs];
+
+    let update_ix =
+        |end_price: Option<u64>, end_time: Option<u64>, top_up: Option<u64>| {
+            create_update_ix(
+                program_id,
+                end_price,
+                end_time,
+                top_up,
+                auction_id,
+                auction_bump,
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+            )
+        };
+
+    let mut send = |ix| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+
+    // Raise end_price
+    assert_auction_error(
+        send(update_ix(Some(end_price + 1), None, None)),
+        AuctionError::InvalidPrices,
+    );
+    // Shorten end_time
+    assert_auction_error(
+        send(update_ix(None, Some(end_time - 1), None)),
+        AuctionError::InvalidEndTime,
+    );
+    // Top up 0
+    assert_auction_error(
+        send(update_ix(None, None, Some(0))),
+        AuctionError::InvalidSellAmount,
+    );
+
+    // Lower end_price, extend end_time and top up
+    let new_end_price = (1.0 * 1e6) as u64;
+    let new_end_time = end_time + 20;
+    let top_up = 1e8 as u64;
+    let res = send(update_ix(
+        Some(new_end_price),
+        Some(new_end_time),
+        Some(top_up),
+    ));
+    assert!(res.is_ok());
+    txs.push(res.unwrap().logs);
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.start_price, start_price);
+    assert_eq!(auction.end_price, new_end_price);
+    assert_eq!(auction.end_time, new_end_time);
+    assert_eq!(auction.sell_amt, sell_amt + top_up);
+    assert_eq!(auction.remaining, sell_amt + top_up);
+    assert_eq!(
+        get_token_balance(&svm, &auction_sell_ata),
+        sell_amt + top_up
+    );
+
+    // Only seller can update
+    let ix = create_update_ix(
+        program_id,
+        Some(new_end_price),
+        None,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Buy
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        1e6 as u64,
+        start_price,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+        None,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    txs.push(res.unwrap().logs);
+
+    // History keeps init parameters and records the update
+    let history = history::get_history(&program_id, &txs);
+    assert_eq!(history.len(), 1);
+    let updated = &history[0];
+    assert_eq!(updated.created.end_price, end_price);
+    assert_eq!(updated.created.end_time, end_time);
+    assert_eq!(updated.created.sell_amt, sell_amt);
+    assert_eq!(updated.updates.len(), 1);
+    assert_eq!(updated.updates[0].auction, auction_pda);
+    assert_eq!(updated.updates[0].end_price, new_end_price);
+    assert_eq!(updated.updates[0].end_time, new_end_time);
+    assert_eq!(updated.updates[0].sell_amt, sell_amt + top_up);
+    assert_eq!(updated.fills.len(), 1);
+    assert_eq!(updated.fills[0].remaining, sell_amt + top_up - 1e6 as u64);
+    assert!(!updated.is_closed());
+
+    // No updates after a buy
+    let ix = update_ix(Some(new_end_price - 1), None, None);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::HasBids);
+
+    // No updates after end_time
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = new_end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    let ix = update_ix(None, Some(new_end_time + 1), None);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionEnded);
+}
+
+#[test]
+fn test_cancel() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Check seller_sell_ata
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidTokenAccount);
+
+    // Cancel
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &seller_sell_ata), 1e9 as u64);
+}
+
+#[test]
+fn test_events() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let init_ix = |start_time: u64| {
+        create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            start_time + 10,
+            sell_amt,
+            AuctionKind::Dutch,
+            PriceCurve::Linear,
+            0,
+            None,
+            0,
+            false,
+            None,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        )
+    };
+    let buy_ix = |amount: u64| {
+        create_buy_ix(
+            program_id,
+            amount,
+            start_price,
+            None,
+            auction_id,
+            auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+            None,
+        )
+    };
+
+    // Logs of every transaction
+    let mut txs = Vec::new();
+
+    // Init
+    let res = svm
+        .send_transaction(Transaction::new_signed_with_payer(
+            &[init_ix(start_time)],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .unwrap();
+    txs.push(res.logs);
+
+    // Buy 1/4 and the rest
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    for amount in [sell_amt / 4, sell_amt - sell_amt / 4] {
+        let res = svm
+            .send_transaction(Transaction::new_signed_with_payer(
+                &[buy_ix(amount)],
+                Some(&buyer.pubkey()),
+                &[&buyer],
+                svm.latest_blockhash(),
+            ))
+            .unwrap();
+        txs.push(res.logs);
+    }
+
+    // Same auction PDA is created again and cancelled
+    let res = svm
+        .send_transaction(Transaction::new_signed_with_payer(
+            &[init_ix(end_time)],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .unwrap();
+    txs.push(res.logs);
+
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    let res = svm
+        .send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .unwrap();
+    txs.push(res.logs);
+
+    // Events of other programs are ignored
+    assert!(history::get_history(&Pubkey::new_unique(), &txs).is_empty());
+
+    let history = history::get_history(&program_id, &txs);
+    assert_eq!(history.len(), 2);
+
+    // Sold out
+    let sold = &history[0];
+    assert_eq!(sold.created.auction, auction_pda);
+    assert_eq!(sold.created.seller, seller.pubkey());
+    assert_eq!(sold.created.start_price, start_price);
+    assert_eq!(sold.created.end_price, end_price);
+    assert_eq!(sold.created.start_time, start_time);
+    assert_eq!(sold.created.end_time, end_time);
+    assert_eq!(sold.created.sell_amt, sell_amt);
+    assert_eq!(sold.created.allowlist_root, [0; 32]);
+    assert_eq!(sold.created.reveal_period, 0);
+    assert!(!sold.created.nft);
+    assert_eq!(sold.created.collection, Pubkey::default());
+    assert!(sold.updates.is_empty());
+    assert_eq!(sold.fills.len(), 2);
+    assert_eq!(sold.fills[0].remaining, sell_amt - sell_amt / 4);
+    assert_eq!(sold.fills[1].remaining, 0);
+    for fill in &sold.fills {
+        assert_eq!(fill.buyer, buyer.pubkey());
+        assert_eq!(fill.price, (1.9 * 1e6) as u64);
+        assert_eq!(fill.timestamp, start_time + 2);
+    }
+    assert_eq!(sold.cancelled, None);
+    assert_eq!(sold.closed.as_ref().unwrap().unsold, 0);
+    assert!(sold.is_closed());
+
+    // Totals are kept after the auction PDA is closed
+    let (sold_amt, paid) = sold.get_totals();
+    assert_eq!(sold_amt, sell_amt);
+    assert_eq!(paid, get_token_balance(&svm, &seller_buy_ata));
+
+    // Cancelled
+    let cancelled = &history[1];
+    assert_eq!(cancelled.created.start_time, end_time);
+    assert!(cancelled.fills.is_empty());
+    assert_eq!(cancelled.cancelled.as_ref().unwrap().amount, sell_amt);
+    assert_eq!(
+        cancelled.cancelled.as_ref().unwrap().timestamp,
+        svm.get_sysvar::<Clock>().unix_timestamp as u64
+    );
+    assert_eq!(cancelled.closed, None);
+    assert!(cancelled.is_closed());
+}
+
+#[test]
+fn test_reclaim() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy 1/4
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let amt = sell_amt / 4;
+    let ix = create_buy_ix(
+        program_id,
+        amt,
+        start_price,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+        None,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Reclaim by anyone
+    let ix = create_reclaim_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        payer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    // Auction not ended
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotEnded);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    let seller_sell_bal = get_token_balance(&svm, &seller_sell_ata);
+    let payer_bal = svm.get_balance(&payer.pubkey()).unwrap();
+    let auction_bal = svm.get_balance(&auction_pda).unwrap();
+    let bounty = auction_bal * Auction::RECLAIM_BOUNTY_BPS / 10000;
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Transaction fee = 5000 lamports
+    assert!(bounty > 0);
+    assert_eq!(
+        svm.get_balance(&payer.pubkey()).unwrap(),
+        payer_bal + bounty - 5000
+    );
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        seller_sell_bal + sell_amt - amt
+    );
+}
+
+#[test]
+fn test_fee() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    let fee_vault = get_ata(&mint_buy, &config_pda);
+
+    // Config already initialized
+    let ix = create_init_config_ix(program_id, 0, config_bump, seller.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Set fee by non admin
+    let fee_bps = 100;
+    let ix = create_set_fee_ix(program_id, fee_bps, seller.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::NotAdmin);
+
+    // Fee > max
+    let ix =
+        create_set_fee_ix(program_id, Config::MAX_FEE_BPS + 1, payer.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidFee);
+
+    // Set fee
+    let ix = create_set_fee_ix(program_id, fee_bps, payer.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&config_pda).unwrap().data;
+    let config = Config::try_from_slice(&data).unwrap();
+    assert_eq!(config.admin, payer.pubkey());
+    assert_eq!(config.fee_bps, fee_bps);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Anyone can send lamports to fee_vault before it is created,
+    // buy still creates it
+    svm.set_account(
+        fee_vault,
+        Account {
+            lamports: 1,
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    // Buy at start price
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        sell_amt,
+        start_price,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+        None,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 100 sell token at 2 buy token, 1% fee
+    let buy_amt = 2 * sell_amt;
+    let fee = buy_amt * fee_bps as u64 / 10000;
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(get_token_balance(&svm, &fee_vault), fee);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), buy_amt - fee);
+
+    // Withdraw fees
+    let payer_buy_ata =
+        create_ata(&mut svm, &payer, &payer.pubkey(), &mint_buy);
+
+    // Withdraw by non admin
+    let ix = create_withdraw_fees_ix(
+        program_id,
+        fee,
+        seller.pubkey(),
+        mint_buy,
+        seller_buy_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::NotAdmin);
+
+    let ix = create_withdraw_fees_ix(
+        program_id,
+        fee,
+        payer.pubkey(),
+        mint_buy,
+        payer_buy_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(get_token_balance(&svm, &fee_vault), 0);
+    assert_eq!(get_token_balance(&svm, &payer_buy_ata), fee);
+}
+
+#[test]
+fn test_init_config() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Remove config created by setup
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    svm.set_account(config_pda, Account::default()).unwrap();
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy without config, no protocol fee
+    let buyer_buy_bal = get_token_balance(&svm, &buyer_buy_ata);
+    let ix = create_buy_ix(
+        program_id,
+        sell_amt,
+        start_price,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+        None,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let paid = buyer_buy_bal - get_token_balance(&svm, &buyer_buy_ata);
+    assert!(paid > 0);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), paid);
+    assert_eq!(svm.get_balance(&config_pda).unwrap_or(0), 0);
+
+    // Init config by an account that is not the upgrade authority
+    let ix = create_init_config_ix(program_id, 0, config_bump, seller.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::NotAdmin);
+
+    // Init config with fee > max
+    let ix = create_init_config_ix(
+        program_id,
+        Config::MAX_FEE_BPS + 1,
+        config_bump,
+        payer.pubkey(),
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidFee);
+
+    // Init config by the upgrade authority
+    let ix = create_init_config_ix(program_id, 0, config_bump, payer.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&config_pda).unwrap().data;
+    let config = Config::try_from_slice(&data).unwrap();
+    assert_eq!(config.admin, payer.pubkey());
+}
+
+#[test]
+fn test_sol() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy: _,
+        seller_sell_ata,
+        seller_buy_ata: _,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda: _,
+        auction_id,
+        auction_bump: _,
+        auction_sell_ata: _,
+    } = setup(&mut svm);
+
+    svm.airdrop(&buyer.pubkey(), 10_000_000_000).unwrap();
+
+    // Auction paid in lamports
+    let mint_buy = Auction::SOL_MINT;
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        auction_id,
+    );
+    let auction_sell_ata = get_ata(&mint_sell, &auction_pda);
+    let (config_pda, _) = get_config_pda(&program_id);
+
+    // 1% fee
+    let fee_bps = 100;
+    let ix = create_set_fee_ix(program_id, fee_bps, payer.pubkey());
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    // 2 SOL to 1 SOL per sell token
+    let start_price = 2_000_000_000;
+    let end_price = 1_000_000_000;
+    let sell_amt = 1e6 as u64;
+
+    let init_ix = |kind: AuctionKind| {
+        create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            kind,
+            PriceCurve::Linear,
+            0,
+            None,
+            0,
+            false,
+            None,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        )
+    };
+
+    // Only Dutch auctions can be paid in SOL
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[init_ix(AuctionKind::English)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAuctionKind);
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[init_ix(AuctionKind::Dutch)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.mint_buy, Auction::SOL_MINT);
+    assert_eq!(auction.decimals_buy, Auction::SOL_DECIMALS);
+
+    // Buy half at 1.5 SOL
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 5) as i64;
+    svm.set_sysvar(&clock);
+
+    let amount = sell_amt / 2;
+    let buy_ix = |buyer_buy_account: Pubkey| {
+        create_buy_ix(
+            program_id,
+            amount,
+            start_price,
+            None,
+            auction_id,
+            auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_account,
+            seller.pubkey(),
+            None,
+        )
+    };
+
+    // Buyer pays from its wallet, not a token account
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(buyer_buy_ata)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidTokenAccount);
+
+    let buyer_lamports = svm.get_balance(&buyer.pubkey()).unwrap();
+    let seller_lamports = svm.get_balance(&seller.pubkey()).unwrap();
+    let config_lamports = svm.get_balance(&config_pda).unwrap();
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[buy_ix(buyer.pubkey())],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 0.5 sell token at 1.5 SOL, 1% fee
+    let buy_amt = 750_000_000;
+    let fee = buy_amt * fee_bps as u64 / 10000;
+    // Transaction fee = 5000 lamports
+    assert_eq!(
+        svm.get_balance(&buyer.pubkey()).unwrap(),
+        buyer_lamports - buy_amt - 5000
+    );
+    assert_eq!(
+        svm.get_balance(&seller.pubkey()).unwrap(),
+        seller_lamports + buy_amt - fee
+    );
+    assert_eq!(svm.get_balance(&config_pda).unwrap(), config_lamports + fee);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), amount);
+
+    // Withdraw SOL fees, config_pda stays rent exempt
+    let dst = Keypair::new().pubkey();
+
+    let ix = create_withdraw_fees_ix(
+        program_id,
+        fee + 1,
+        payer.pubkey(),
+        mint_buy,
+        dst,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    let ix =
+        create_withdraw_fees_ix(program_id, fee, payer.pubkey(), mint_buy, dst);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&dst).unwrap(), fee);
+    assert_eq!(svm.get_balance(&config_pda).unwrap(), config_lamports);
+}
+
+#[test]
+fn test_nft() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata: _,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup_with_decimals(&mut svm, 0, 6);
+
+    // NFT, decimals 0 and supply 1
+    let nft = create_mint(&mut svm, &payer, 0);
+    let seller_nft_ata = create_ata(&mut svm, &payer, &seller.pubkey(), &nft);
+    mint_to(&mut svm, &payer, &nft, &seller_nft_ata, 1);
+
+    let (nft_auction_pda, nft_auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &nft,
+        &mint_buy,
+        auction_id,
+    );
+    let auction_nft_ata = get_ata(&nft, &nft_auction_pda);
+    let buyer_nft_ata = get_ata(&nft, &buyer.pubkey());
+
+    // 5% royalties split 60 / 40
+    let creator_a = Keypair::new().pubkey();
+    let creator_b = Keypair::new().pubkey();
+    let creator_a_buy_ata = create_ata(&mut svm, &payer, &creator_a, &mint_buy);
+    let creator_b_buy_ata = get_ata(&mint_buy, &creator_b);
+    let collection = Pubkey::new_unique();
+
+    create_metadata(
+        &mut svm,
+        &nft,
+        500,
+        &[(creator_a, 60), (creator_b, 40)],
+        Some(collection),
+    );
+    create_metadata(&mut svm, &mint_sell, 500, &[], None);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (10.0 * 1e6) as u64;
+    let end_price = (5.0 * 1e6) as u64;
+
+    // Init NFT auction
+    let init_ix = |sell_amt: u64,
+                   collection: Option<Pubkey>,
+                   mint_sell: Pubkey,
+                   auction_pda: Pubkey,
+                   auction_bump: u8,
+                   auction_sell_ata: Pubkey,
+                   seller_sell_ata: Pubkey| {
+        create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            AuctionKind::Dutch,
+            PriceCurve::Linear,
+            0,
+            None,
+            0,
+            true,
+            collection,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        )
+    };
+
+    // Fungible mint
+    let ix = init_ix(
+        1,
+        None,
+        mint_sell,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidNft);
+
+    // sell_amt != 1
+    let ix = init_ix(
+        2,
+        None,
+        nft,
+        nft_auction_pda,
+        nft_auction_bump,
+        auction_nft_ata,
+        seller_nft_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidNft);
+
+    // Wrong collection
+    let ix = init_ix(
+        1,
+        Some(Pubkey::new_unique()),
+        nft,
+        nft_auction_pda,
+        nft_auction_bump,
+        auction_nft_ata,
+        seller_nft_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidCollection);
+
+    let ix = init_ix(
+        1,
+        Some(collection),
+        nft,
+        nft_auction_pda,
+        nft_auction_bump,
+        auction_nft_ata,
+        seller_nft_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&nft_auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert!(auction.nft);
+    assert_eq!(auction.collection, collection);
+    assert_eq!(get_token_balance(&svm, &auction_nft_ata), 1);
+
+    // Buy at start price
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buy_ix = || {
+        create_buy_ix(
+            program_id,
+            1,
+            start_price,
+            None,
+            auction_id,
+            nft_auction_bump,
+            buyer.pubkey(),
+            seller.pubkey(),
+            nft,
+            mint_buy,
+            nft_auction_pda,
+            auction_nft_ata,
+            buyer_nft_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+            None,
+        )
+    };
+
+    // Missing creator accounts
+    let mut ix = buy_ix();
+    ix.accounts
+        .extend(get_royalty_accounts(&nft, &mint_buy, &[creator_a]));
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Creators in wrong order
+    let mut ix = buy_ix();
+    ix.accounts.extend(get_royalty_accounts(
+        &nft,
+        &mint_buy,
+        &[creator_b, creator_a],
+    ));
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidCreator);
+
+    let buyer_buy_before = get_token_balance(&svm, &buyer_buy_ata);
+
+    let mut ix = buy_ix();
+    ix.accounts.extend(get_royalty_accounts(
+        &nft,
+        &mint_buy,
+        &[creator_a, creator_b],
+    ));
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 10 buy token, 0.5 royalties, no protocol fee
+    let buy_amt = start_price;
+    assert_eq!(get_token_balance(&svm, &buyer_nft_ata), 1);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_buy_ata),
+        buyer_buy_before - buy_amt
+    );
+    assert_eq!(get_token_balance(&svm, &creator_a_buy_ata), 300_000);
+    assert_eq!(get_token_balance(&svm, &creator_b_buy_ata), 200_000);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), buy_amt - 500_000);
+}
+
+#[test]
+fn test_royalties() {
+    let creator = Creator {
+        address: Pubkey::new_unique(),
+        verified: true,
+        share: 50,
+    };
+    let metadata = Metadata {
+        key: KEY_METADATA_V1,
+        update_authority: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        data: Data {
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            seller_fee_basis_points: MAX_ROYALTY_BPS,
+            creators: Some(vec![creator.clone(), creator]),
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: None,
+    };
+
+    // 100% royalties
+    assert_eq!(metadata::get_royalties(&metadata, 1000, 1000), [500, 500]);
+    // Capped at buy_amt - fee, 10% protocol fee
+    assert_eq!(metadata::get_royalties(&metadata, 1000, 900), [450, 450]);
+
+    // Unverified creator is not paid
+    let mut metadata = metadata;
+    metadata.data.creators.as_mut().unwrap()[1].verified = false;
+    assert_eq!(metadata::get_royalties(&metadata, 1000, 1000), [500, 0]);
+}
+
+#[test]
+fn test_english_auction() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let auction_buy_ata = get_ata(&mint_buy, &auction_pda);
+
+    // Second bidder
+    let bidder = Keypair::new();
+    svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+    let bidder_buy_ata =
+        create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+    mint_to(&mut svm, &payer, &mint_buy, &bidder_buy_ata, 1e9 as u64);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::English,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    let init_logs = res.unwrap().logs;
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Check bid >= end_price
+    let ix = create_bid_ix(
+        program_id,
+        end_price - 1,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_buy_ata,
+        buyer_buy_ata,
+        buyer.pubkey(),
+        buyer_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::BidTooLow);
+
+    // Buy is only for Dutch auctions
+    let ix = create_buy_ix(
+        program_id,
+        sell_amt,
+        start_price,
+        None,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+        None,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAuctionKind);
+
+    // Bid
+    let price_0 = (1.2 * 1e6) as u64;
+    let ix = create_bid_ix(
+        program_id,
+        price_0,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_buy_ata,
+        buyer_buy_ata,
+        buyer.pubkey(),
+        buyer_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let buy_amt_0 = sell_amt * price_0 / (1e6 as u64);
+    assert_eq!(get_token_balance(&svm, &auction_buy_ata), buy_amt_0);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_buy_ata),
+        1e9 as u64 - buy_amt_0
+    );
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.highest_bid, price_0);
+    assert_eq!(auction.highest_bidder, buyer.pubkey());
+
+    // Check bid > highest bid
+    let ix = create_bid_ix(
+        program_id,
+        price_0,
+        auction_id,
+        auction_bump,
+        bidder.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_buy_ata,
+        bidder_buy_ata,
+        buyer.pubkey(),
+        buyer_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bidder.pubkey()),
+        &[&bidder],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::BidTooLow);
+
+    // Check cancel fails once there is a bid
+    let ix = create_cancel_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::HasBids);
+
+    // Outbid, previous bidder is refunded
+    let price_1 = (1.5 * 1e6) as u64;
+    let ix = create_bid_ix(
+        program_id,
+        price_1,
+        auction_id,
+        auction_bump,
+        bidder.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_buy_ata,
+        bidder_buy_ata,
+        buyer.pubkey(),
+        buyer_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bidder.pubkey()),
+        &[&bidder],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let buy_amt_1 = sell_amt * price_1 / (1e6 as u64);
+    assert_eq!(get_token_balance(&svm, &auction_buy_ata), buy_amt_1);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 1e9 as u64);
+    assert_eq!(
+        get_token_balance(&svm, &bidder_buy_ata),
+        1e9 as u64 - buy_amt_1
+    );
+
+    // Highest bidder closes bidder_buy_ata and anyone sends lamports to its
+    // address, outbid still refunds into a new ATA
+    svm.set_account(
+        bidder_buy_ata,
+        Account {
+            lamports: 1,
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    let price_2 = (1.8 * 1e6) as u64;
+    let ix = create_bid_ix(
+        program_id,
+        price_2,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_buy_ata,
+        buyer_buy_ata,
+        bidder.pubkey(),
+        bidder_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let buy_amt_2 = sell_amt * price_2 / (1e6 as u64);
+    assert_eq!(get_token_balance(&svm, &auction_buy_ata), buy_amt_2);
+    assert_eq!(get_token_balance(&svm, &bidder_buy_ata), buy_amt_1);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_buy_ata),
+        1e9 as u64 - buy_amt_2
+    );
+
+    // Check settle fails before end_time
+    let ix = create_settle_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        payer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        auction_buy_ata,
+        buyer.pubkey(),
+        buyer_sell_ata,
+        seller_sell_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotEnded);
+
+    // Seller closed seller_buy_ata and anyone sent lamports to its address,
+    // settle recreates it
+    svm.set_account(
+        seller_buy_ata,
+        Account {
+            lamports: 1,
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    // Settle
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    let settle_logs = res.unwrap().logs;
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_buy_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), buy_amt_2);
+
+    // Settle is recorded as a fill by the winner and closes the auction
+    let history = history::get_history(&program_id, &[init_logs, settle_logs]);
+    assert_eq!(history.len(), 1);
+    let settled = &history[0];
+    assert_eq!(settled.fills.len(), 1);
+    assert_eq!(settled.fills[0].buyer, buyer.pubkey());
+    assert_eq!(settled.fills[0].amount, sell_amt);
+    assert_eq!(settled.fills[0].price, price_2);
+    assert_eq!(settled.fills[0].buy_amt, buy_amt_2);
+    assert_eq!(settled.closed.as_ref().unwrap().unsold, 0);
+    assert!(settled.is_closed());
+}
+
+// Init a Dutch auction with curve, then buy 1 sell token at each
+// (seconds after start_time, expected price)
+#[test]
+fn test_batch_auction() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init, end_price is the reserve price
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = 1e6 as u64;
+    let end_price = 1e6 as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Batch,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    let mut txs = vec![res.unwrap().logs];
+
+    // More bidders, 1000 buy tokens each
+    let bidders: Vec<Keypair> = (0..4).map(|_| Keypair::new()).collect();
+    for bidder in bidders.iter() {
+        svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+        let bidder_buy_ata =
+            create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+        mint_to(&mut svm, &payer, &mint_buy, &bidder_buy_ata, 1e9 as u64);
+    }
+    let bidders: Vec<&Keypair> =
+        std::iter::once(&buyer).chain(bidders.iter()).collect();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let batch_bid_ix = |svm: &LiteSVM, bidder: &Keypair, quantity, price| {
+        let (prev_pda, next_pda) =
+            get_batch_bid_position(svm, &auction_pda, price);
+        create_batch_bid_ix(
+            program_id,
+            quantity,
+            price,
+            auction_id,
+            auction_bump,
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            prev_pda,
+            next_pda,
+        )
+    };
+
+    // Bid below reserve price
+    let ix = batch_bid_ix(&svm, &buyer, 1e7 as u64, end_price - 1);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::BidTooLow);
+
+    // Bid quantity > sell_amt
+    let ix = batch_bid_ix(&svm, &buyer, sell_amt + 1, end_price);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidAmount);
+
+    // 60 @ 3, 40 @ 2, 40 @ 2, 10 @ 1, 0.000001 @ 1
+    // 60 tokens fill at 3, leaving 40 tokens for 80 tokens bid at 2
+    // Any quantity can be bid, the smallest bid is not filled
+    let orders = [(6e7, 3e6), (4e7, 2e6), (4e7, 2e6), (1e7, 1e6), (1.0, 1e6)];
+    for (bidder, (quantity, price)) in bidders.iter().zip(orders) {
+        let ix = batch_bid_ix(&svm, bidder, quantity as u64, price as u64);
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&bidder.pubkey()),
+            &[*bidder],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+    }
+
+    // Bids are sorted by price, equal prices in bid order
+    let bid_pdas: Vec<Pubkey> = bidders
+        .iter()
+        .map(|bidder| {
+            get_batch_bid_pda(&program_id, &auction_pda, &bidder.pubkey()).0
+        })
+        .collect();
+    assert_eq!(get_batch_bids(&svm, &auction_pda), bid_pdas);
+
+    // Bid out of price order, 2 @ 3 inserted after 10 @ 1
+    let late = Keypair::new();
+    svm.airdrop(&late.pubkey(), 1_000_000_000).unwrap();
+    let late_buy_ata = create_ata(&mut svm, &payer, &late.pubkey(), &mint_buy);
+    mint_to(&mut svm, &payer, &mint_buy, &late_buy_ata, 1e9 as u64);
+    for (prev_pda, next_pda) in [
+        (bid_pdas[3], bid_pdas[4]),
+        (auction_pda, bid_pdas[0]),
+        (bid_pdas[0], bid_pdas[2]),
+    ] {
+        let ix = create_batch_bid_ix(
+            program_id,
+            2e6 as u64,
+            3e6 as u64,
+            auction_id,
+            auction_bump,
+            late.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            prev_pda,
+            next_pda,
+        );
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&late.pubkey()),
+            &[&late],
+            svm.latest_blockhash(),
+        ));
+        assert_auction_error(res, AuctionError::InvalidBidOrder);
+    }
+
+    // 1 bid per bidder
+    let ix = batch_bid_ix(&svm, &buyer, 1e7 as u64, end_price);
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Second bid at a non canonical bump
+    let (other_bid_pda, other_bid_bump) = get_non_canonical_pda(
+        &program_id,
+        &[
+            BatchBid::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.pubkey().as_ref(),
+        ],
+    );
+    let mut ix = batch_bid_ix(&svm, &buyer, 1e7 as u64, end_price);
+    ix.accounts[5].pubkey = other_bid_pda;
+    ix.data = borsh::to_vec(&Cmd::BatchBid {
+        quantity: 1e7 as u64,
+        price: end_price,
+        auction_id,
+        bump: auction_bump,
+        bid_bump: other_bid_bump,
+    })
+    .unwrap();
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_instruction_error(res, InstructionError::InvalidSeeds);
+
+    // Escrow of 180 + 80 + 80 + 10 + 0.000001
+    let auction_buy_ata = get_ata(&mint_buy, &auction_pda);
+    assert_eq!(
+        get_token_balance(&svm, &auction_buy_ata),
+        350 * 1e6 as u64 + 1
+    );
+
+    let clear_ix = |bid_pdas: &[Pubkey]| {
+        create_batch_clear_ix(
+            program_id,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            bid_pdas,
+        )
+    };
+
+    // Auction not ended
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[clear_ix(&bid_pdas)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotEnded);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    // Settle before clear
+    let ix_settle = create_batch_settle_ix(
+        program_id,
+        auction_id,
+        auction_bump,
+        payer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        buyer.pubkey(),
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix_settle],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::NotCleared);
+
+    // Clear starting after the highest bid
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[clear_ix(&bid_pdas[1..])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidBidOrder);
+
+    // Clear over 2 transactions, first 2 bids
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[clear_ix(&bid_pdas[..2])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.clearing_price, 0);
+
+    // Same bid counted twice
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[clear_ix(&bid_pdas[1..])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidBidOrder);
+
+    // Rest of the bids, supply runs out before the bids at 1
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[clear_ix(&bid_pdas[2..])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.clearing_price, 2e6 as u64);
+    assert_eq!(auction.tie_supply, 4e7 as u64);
+    assert_eq!(auction.tie_demand, 8e7 as u128);
+
+    // Anyone sends buy token to auction_buy_ata
+    mint_to(&mut svm, &payer, &mint_buy, &auction_buy_ata, 1);
+
+    // Settle every bid
+    svm.expire_blockhash();
+
+    let mut buy_bals: Vec<u64> = bidders
+        .iter()
+        .map(|bidder| {
+            get_token_balance(&svm, &get_ata(&mint_buy, &bidder.pubkey()))
+        })
+        .collect();
+
+    // Seller and last bidder closed their buy token accounts and anyone sent
+    // lamports to their addresses, settle recreates them before paying out
+    let last_bidder_buy_ata = get_ata(&mint_buy, &bidders[3].pubkey());
+    for ata in [seller_buy_ata, last_bidder_buy_ata] {
+        svm.set_account(
+            ata,
+            Account {
+                lamports: 1,
+                ..Account::default()
+            },
+        )
+        .unwrap();
+    }
+    buy_bals[3] = 0;
+
+    for bidder in bidders.iter() {
+        let ix = create_batch_settle_ix(
+            program_id,
+            auction_id,
+            auction_bump,
+            payer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            bidder.pubkey(),
+        );
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+        txs.push(res.unwrap().logs);
+    }
+
+    // Filled at clearing price 2, tied bids get 40 * 40 / 80 = 20 each
+    // (sell token filled, buy token refunded)
+    let fills = [(6e7, 6e7), (2e7, 4e7), (2e7, 4e7), (0.0, 1e7), (0.0, 1.0)];
+    for ((bidder, buy_bal), (filled, refund)) in
+        bidders.iter().zip(buy_bals).zip(fills)
+    {
+        let bidder_sell_ata = get_ata(&mint_sell, &bidder.pubkey());
+        let bidder_buy_ata = get_ata(&mint_buy, &bidder.pubkey());
+        assert_eq!(get_token_balance(&svm, &bidder_sell_ata), filled as u64);
+        assert_eq!(
+            get_token_balance(&svm, &bidder_buy_ata),
+            buy_bal + refund as u64
+        );
+    }
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 6e7 as u64);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 880 * 1e6 as u64);
+
+    // Seller receives 100 tokens at 2 and the donated buy token
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 2e8 as u64 + 1);
+
+    // Auction closed after last bid is settled
+    for bid_pda in bid_pdas.iter() {
+        assert_eq!(svm.get_balance(bid_pda).unwrap_or(0), 0);
+    }
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_buy_ata).unwrap_or(0), 0);
+
+    // Every filled bid is recorded, last settle closes the auction
+    let history = history::get_history(&program_id, &txs);
+    assert_eq!(history.len(), 1);
+    let settled = &history[0];
+    assert_eq!(settled.fills.len(), 3);
+    for fill in &settled.fills {
+        assert_eq!(fill.price, 2e6 as u64);
+    }
+    assert_eq!(settled.get_totals(), (sell_amt, 2e8 as u64));
+    assert_eq!(settled.closed.as_ref().unwrap().unsold, 0);
+    assert!(settled.is_closed());
+}
+
+#[test]
+fn test_sealed_auction() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    // Init, end_price is the reserve price
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let reveal_period = 10;
+    let start_price = 1e6 as u64;
+    let end_price = 1e6 as u64;
+    let sell_amt = 1e8 as u64;
+
+    // Sealed auction without reveal period
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Sealed,
+        PriceCurve::Linear,
+        0,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidRevealPeriod);
+
+    // Reveal period > MAX_REVEAL_PERIOD
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Sealed,
+        PriceCurve::Linear,
+        0,
+        None,
+        Auction::MAX_REVEAL_PERIOD + 1,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::InvalidRevealPeriod);
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Sealed,
+        PriceCurve::Linear,
+        0,
+        None,
+        reveal_period,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // More bidders, 1000 buy tokens each
+    let bidders: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for bidder in bidders.iter() {
+        svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+        let bidder_buy_ata =
+            create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+        mint_to(&mut svm, &payer, &mint_buy, &bidder_buy_ata, 1e9 as u64);
+    }
+    let bidders: Vec<&Keypair> =
+        std::iter::once(&buyer).chain(bidders.iter()).collect();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Commit (price, deposit)
+    // 3 revealed, 2 revealed, 5 not revealed,
+    // 4 with deposit < price of 100 tokens can't be revealed
+    let salt = [7; 32];
+    let bids = [(3e6, 4e8), (2e6, 3e8), (5e6, 6e8), (4e6, 1e8)];
+    for (bidder, (price, deposit)) in bidders.iter().zip(bids) {
+        let commitment = get_commitment(&bidder.pubkey(), price as u64, &salt);
+        let ix = create_sealed_commit_ix(
+            program_id,
+            commitment,
+            deposit as u64,
+            auction_id,
+            auction_bump,
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+        );
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&bidder.pubkey()),
+            &[*bidder],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+    }
+
+    // Second bid at a non canonical bump, a low bid could be settled as
+    // the winner of the high bid
+    let (other_bid_pda, other_bid_bump) = get_non_canonical_pda(
+        &program_id,
+        &[
+            SealedBid::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.pubkey().as_ref(),
+        ],
+    );
+    let commitment = get_commitment(&buyer.pubkey(), 1e6 as u64, &salt);
+    let mut ix = create_sealed_commit_ix(
+        program_id,
+        commitment,
+        1e8 as u64,
+        auction_id,
+        auction_bump,
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+    );
+    ix.accounts[5].pubkey = other_bid_pda;
+    ix.data = borsh::to_vec(&Cmd::SealedCommit {
+        commitment,
+        deposit: 1e8 as u64,
+        auction_id,
+        bump: auction_bump,
+        bid_bump: other_bid_bump,
+    })
+    .unwrap();
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_instruction_error(res, InstructionError::InvalidSeeds);
+
+    let auction_buy_ata = get_ata(&mint_buy, &auction_pda);
+    assert_eq!(get_token_balance(&svm, &auction_buy_ata), 14e8 as u64);
+
+    let reveal_ix = |bidder: &Keypair, price: u64, salt: [u8; 32]| {
+        create_sealed_reveal_ix(
+            program_id,
+            price,
+            salt,
+            auction_id,
+            auction_bump,
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+        )
+    };
+
+    // Reveal during bid phase
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[reveal_ix(&buyer, 3e6 as u64, salt)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotEnded);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    // Commit after bid phase
+    let late = Keypair::new();
+    svm.airdrop(&late.pubkey(), 1_000_000_000).unwrap();
+    let ix = create_sealed_commit_ix(
+        program_id,
+        get_commitment(&late.pubkey(), 6e6 as u64, &salt),
+        1e9 as u64,
+        auction_id,
+        auction_bump,
+        late.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&late.pubkey()),
+        &[&late],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionEnded);
+
+    // Wrong salt
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[reveal_ix(&buyer, 3e6 as u64, [8; 32])],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Wrong price
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[reveal_ix(&buyer, 4e6 as u64, salt)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Deposit < price of 100 tokens
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[reveal_ix(bidders[3], 4e6 as u64, salt)],
+        Some(&bidders[3].pubkey()),
+        &[bidders[3]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Reveal
+    for (bidder, price) in [(bidders[1], 2e6), (bidders[0], 3e6)] {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[reveal_ix(bidder, price as u64, salt)],
+            Some(&bidder.pubkey()),
+            &[bidder],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+    }
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::try_from_slice(&data).unwrap();
+    assert_eq!(auction.highest_bid, 3e6 as u64);
+    assert_eq!(auction.highest_bidder, buyer.pubkey());
+    assert_eq!(
+        auction.highest_bid_pda,
+        get_sealed_bid_pda(&program_id, &auction_pda, &buyer.pubkey()).0
+    );
+    assert_eq!(auction.num_bids, 4);
+
+    let settle_ix = |bidder: &Keypair| {
+        create_sealed_settle_ix(
+            program_id,
+            auction_id,
+            auction_bump,
+            payer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            bidder.pubkey(),
+        )
+    };
+
+    // Settle during reveal phase
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[settle_ix(&buyer)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionNotEnded);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (end_time + reveal_period) as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
+    // Reveal after reveal phase
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[reveal_ix(bidders[2], 5e6 as u64, salt)],
+        Some(&bidders[2].pubkey()),
+        &[bidders[2]],
+        svm.latest_blockhash(),
+    ));
+    assert_auction_error(res, AuctionError::AuctionEnded);
+
+    // Anyone sends buy token to auction_buy_ata
+    mint_to(&mut svm, &payer, &mint_buy, &auction_buy_ata, 1);
+
+    // Settle every bid
+    let mut buy_bals: Vec<u64> = bidders
+        .iter()
+        .map(|bidder| {
+            get_token_balance(&svm, &get_ata(&mint_buy, &bidder.pubkey()))
+        })
+        .collect();
+
+    // Seller and revealed loser closed their buy token accounts and anyone
+    // sent lamports to their addresses, settle recreates them before paying
+    let loser_buy_ata = get_ata(&mint_buy, &bidders[1].pubkey());
+    for ata in [seller_buy_ata, loser_buy_ata] {
+        svm.set_account(
+            ata,
+            Account {
+                lamports: 1,
+                ..Account::default()
+            },
+        )
+        .unwrap();
+    }
+    buy_bals[1] = 0;
+
+    for bidder in bidders.iter() {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[settle_ix(*bidder)],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok());
+    }
+
+    // Winner pays 100 tokens at 3, revealed loser is refunded,
+    // unrevealed bids lose 10% of deposit
+    let refunds = [1e8, 3e8, 5.4e8, 0.9e8];
+    for ((bidder, buy_bal), refund) in bidders.iter().zip(buy_bals).zip(refunds)
+    {
+        let bidder_buy_ata = get_ata(&mint_buy, &bidder.pubkey());
+        assert_eq!(
+            get_token_balance(&svm, &bidder_buy_ata),
+            buy_bal + refund as u64
+        );
+    }
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 700 * 1e6 as u64);
+
+    // 300 from winner + 60 + 10 penalties and the donated buy token
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 3.7e8 as u64 + 1);
+
+    // Auction closed after last bid is settled
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_buy_ata).unwrap_or(0), 0);
+}
+
+fn check_prices(curve: PriceCurve, curve_period: u64, prices: &[(u64, u64)]) {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        AuctionKind::Dutch,
+        curve,
+        curve_period,
+        None,
+        0,
+        false,
+        None,
+        auction_id,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 1 sell token, buy_amt = price
+    let amount = 1e6 as u64;
+
+    for &(dt, price) in prices {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = (start_time + dt) as i64;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+
+        let buy_ix = |max_price: u64| {
+            create_buy_ix(
+                program_id,
+                amount,
+                max_price,
+                None,
+                auction_id,
+                auction_bump,
+                buyer.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                buyer_sell_ata,
+                buyer_buy_ata,
+                seller_buy_ata,
+                None,
+            )
+        };
+
+        // Price > max_price
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[buy_ix(price - 1)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ));
+        assert_auction_error(res, AuctionError::MaxPrice);
+
+        let seller_buy_bal = get_token_balance(&svm, &seller_buy_ata);
+
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[buy_ix(price)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ));
+        assert!(res.is_ok(), "price at {} <= {}", dt, price);
+
+        assert_eq!(
+            get_token_balance(&svm, &seller_buy_ata) - seller_buy_bal,
+            price,
+            "price at {}",
+            dt
+        );
+    }
+}
+
+#[test]
+fn test_linear_curve() {
+    check_prices(
+        PriceCurve::Linear,
+        0,
+        &[
+            (0, 2_000_000),
+            (25, 1_750_000),
+            (50, 1_500_000),
+            (99, 1_010_000),
+        ],
+    );
+}
+
+#[test]
+fn test_exponential_curve() {
+    // Half life = 10 seconds
+    check_prices(
+        PriceCurve::Exponential,
+        10,
+        &[
+            (0, 2_000_000),
+            (10, 1_500_000),
+            (15, 1_375_000),
+            (20, 1_250_000),
+            (99, 1_001_074),
+        ],
+    );
+}
+
+#[test]
+fn test_step_curve() {
+    // 3 steps of 30 seconds
+    check_prices(
+        PriceCurve::Step,
+        30,
+        &[
+            (0, 2_000_000),
+            (29, 2_000_000),
+            (30, 1_666_667),
+            (60, 1_333_334),
+            (90, 1_000_000),
+            (99, 1_000_000),
+        ],
+    );
+}
+
+#[test]
+fn test_init_curve_period() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_id,
+        auction_bump,
+        auction_sell_ata,
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    // Linear with curve_period, Exponential without half life,
+    // Step longer than the auction
+    for (curve, curve_period) in [
+        (PriceCurve::Linear, 10),
+        (PriceCurve::Exponential, 0),
+        (PriceCurve::Step, 0),
+        (PriceCurve::Step, 101),
+    ] {
+        let ix = create_init_ix(
+            program_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            AuctionKind::Dutch,
+            curve,
+            curve_period,
+            None,
+            0,
+            false,
+            None,
+            auction_id,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        );
+
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ));
+        assert_auction_error(res, AuctionError::InvalidCurvePeriod);
+    }
 }
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum Error {
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Return data was not set by the oracle program")]
+    InvalidReturnData,
+}
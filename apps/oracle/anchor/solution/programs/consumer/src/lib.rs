@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+
+use oracle::program::Oracle as OracleProgram;
+use oracle::state::{Oracle, Price};
+
+pub mod error;
+
+// Example of a program reading prices from the oracle program
+declare_id!("Fkn35k5rQFyLiFZKLBfv47c6YeZkp481caG2BPy5nCgX");
+
+// Decimals of collateral value
+pub const USD_DECIMALS: u8 = 6;
+
+// Feed of the collateral price
+pub const FEED_ID: u64 = 1;
+
+// Key that created the oracle of FEED_ID. Anyone can create an oracle of
+// FEED_ID under their own key, only the oracle PDA of this key is trusted.
+pub const ORACLE_OWNER: Pubkey =
+    pubkey!("9Sprik2bbBUiy5tPowgiMZ53fPzhDD5KjLx7njrTnsgH");
+
+// Oracle PDA of FEED_ID created by ORACLE_OWNER
+pub fn get_oracle_pda() -> Pubkey {
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            ORACLE_OWNER.as_ref(),
+            &FEED_ID.to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    pda
+}
+
+#[program]
+pub mod consumer {
+    use super::*;
+
+    // Value in USD of amount of a collateral token with decimals, priced by
+    // an oracle updated at most max_age seconds ago
+    pub fn get_value(
+        ctx: Context<GetValue>,
+        amount: u64,
+        decimals: u8,
+        max_age: u64,
+    ) -> Result<u64> {
+        // Fails if the price is stale or the oracle is halted
+        let now = Clock::get()?.unix_timestamp;
+        let price =
+            ctx.accounts.oracle.get_price_no_older_than(now, max_age)?;
+
+        calc_value(&price, amount, decimals)
+    }
+
+    // Same as get_value, the price is read through CPI to get_price of the
+    // oracle program
+    pub fn get_value_cpi(
+        ctx: Context<GetValueCpi>,
+        amount: u64,
+        decimals: u8,
+        max_age: u64,
+    ) -> Result<u64> {
+        // Oracle program checks it owns oracle, fails if the price is stale
+        // or the oracle is halted
+        oracle::cpi::get_price(
+            CpiContext::new(
+                ctx.accounts.oracle_program.to_account_info(),
+                oracle::cpi::accounts::GetPrice {
+                    oracle: ctx.accounts.oracle.to_account_info(),
+                },
+            ),
+            max_age,
+        )?;
+
+        // Return::get doesn't check which program set the return data
+        let (program_id, data) =
+            get_return_data().ok_or(error::Error::InvalidReturnData)?;
+        require_keys_eq!(
+            program_id,
+            oracle::ID,
+            error::Error::InvalidReturnData
+        );
+        let price = Price::try_from_slice(&data)?;
+
+        calc_value(&price, amount, decimals)
+    }
+}
+
+#[derive(Accounts)]
+pub struct GetValue<'info> {
+    // Account checks that oracle is owned by the oracle program, oracle::ID,
+    // and has the Oracle discriminator. An account with the same data owned
+    // by another program is rejected. Seeds check that oracle is the oracle
+    // of FEED_ID created by ORACLE_OWNER.
+    #[account(
+        seeds = [
+            Oracle::SEED_PREFIX,
+            ORACLE_OWNER.as_ref(),
+            &FEED_ID.to_le_bytes()
+        ],
+        bump,
+        seeds::program = oracle::ID
+    )]
+    pub oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct GetValueCpi<'info> {
+    /// CHECK: oracle PDA of FEED_ID created by ORACLE_OWNER, oracle program
+    /// checks owner and discriminator
+    #[account(
+        seeds = [
+            Oracle::SEED_PREFIX,
+            ORACLE_OWNER.as_ref(),
+            &FEED_ID.to_le_bytes()
+        ],
+        bump,
+        seeds::program = oracle::ID
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    // Check oracle_program is the oracle program, any program can set
+    // return data in the layout of Price
+    pub oracle_program: Program<'info, OracleProgram>,
+}
+
+// Value in USD with USD_DECIMALS decimals, returned through return data.
+// Collateral is valued at the lower bound of the price, price - confidence.
+fn calc_value(price: &Price, amount: u64, decimals: u8) -> Result<u64> {
+    let value = price
+        .get_value(amount, decimals, USD_DECIMALS)
+        .ok_or(error::Error::MathOverflow)?;
+
+    msg!("Collateral value: {}", value);
+
+    Ok(value)
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum Error {
+    #[msg("Price is stale")]
+    StalePrice,
+    #[msg("Oracle is halted")]
+    Halted,
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Oracle;
+
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    pub new_owner: Signer<'info>,
+    #[account(mut, constraint = oracle.pending_owner == new_owner.key())]
+    // Check oracle.pending_owner == new_owner
+    // Nobody can sign for Pubkey::default(), so this fails without a proposal
+    pub oracle: Account<'info, Oracle>,
+}
+
+pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.owner = oracle.pending_owner;
+    oracle.pending_owner = Pubkey::default();
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Oracle, Price};
+
+#[derive(Accounts)]
+pub struct GetPrice<'info> {
+    // Check oracle is owned by this program
+    pub oracle: Account<'info, Oracle>,
+}
+
+// Price for programs calling through CPI, returned through return data.
+// Fails if the price is stale or the oracle is halted.
+pub fn get_price(ctx: Context<GetPrice>, max_age: u64) -> Result<Price> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.oracle.get_price_no_older_than(now, max_age)
+}
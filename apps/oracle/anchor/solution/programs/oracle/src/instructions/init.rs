@@ -3,6 +3,7 @@ use anchor_lang::prelude::*;
 use crate::state::Oracle;
 
 #[derive(Accounts)]
+#[instruction(feed_id: u64)]
 pub struct Init<'info> {
     // Order of fields in the struct is the order of accounts the client must pass them in
     // mut - Program can update account's data or lamports
@@ -17,15 +18,34 @@ pub struct Init<'info> {
         init,
         // 8 = Anchor discriminator
         space = 8 + Oracle::INIT_SPACE,
-        payer = payer
+        payer = payer,
+        // PDA of owner and feed_id. Anyone can create an oracle of any
+        // feed_id, but only under their own key.
+        seeds = [
+            Oracle::SEED_PREFIX,
+            owner.key().as_ref(),
+            &feed_id.to_le_bytes()
+        ],
+        bump
     )]
     pub oracle: Account<'info, Oracle>,
     // Required to create oracle account
     pub system_program: Program<'info, System>,
 }
 
-pub fn init(ctx: Context<Init>, price: u64) -> Result<()> {
-    ctx.accounts.oracle.owner = ctx.accounts.owner.key();
-    ctx.accounts.oracle.price = price;
+pub fn init(
+    ctx: Context<Init>,
+    feed_id: u64,
+    price: u64,
+    exponent: i32,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.owner = ctx.accounts.owner.key();
+    oracle.feed_id = feed_id;
+    oracle.price = price;
+    oracle.exponent = exponent;
+    oracle.last_update_slot = clock.slot;
+    oracle.last_update_ts = clock.unix_timestamp;
     Ok(())
 }
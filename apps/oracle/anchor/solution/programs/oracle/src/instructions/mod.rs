@@ -1,5 +1,13 @@
+mod accept_owner;
+mod get_price;
 mod init;
+mod propose_owner;
+mod set_halted;
 mod update;
 
+pub use accept_owner::*;
+pub use get_price::*;
 pub use init::*;
+pub use propose_owner::*;
+pub use set_halted::*;
 pub use update::*;
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Oracle;
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, constraint = oracle.owner == owner.key())]
+    // Check oracle.owner == owner
+    pub oracle: Account<'info, Oracle>,
+}
+
+// Pubkey::default() cancels a pending proposal.
+// Overwrites any pending proposal.
+pub fn propose_owner(
+    ctx: Context<ProposeOwner>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    ctx.accounts.oracle.pending_owner = new_owner;
+    Ok(())
+}
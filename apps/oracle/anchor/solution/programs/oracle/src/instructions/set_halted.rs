@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Oracle;
+
+#[derive(Accounts)]
+pub struct SetHalted<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, constraint = oracle.owner == owner.key())]
+    // Check oracle.owner == owner
+    pub oracle: Account<'info, Oracle>,
+}
+
+// Owner halts the oracle when its price can't be trusted, consumers reject
+// the price until it is cleared
+pub fn set_halted(ctx: Context<SetHalted>, halted: bool) -> Result<()> {
+    ctx.accounts.oracle.halted = halted;
+    Ok(())
+}
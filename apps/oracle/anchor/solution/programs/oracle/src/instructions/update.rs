@@ -11,7 +11,12 @@ pub struct Update<'info> {
     pub oracle: Account<'info, Oracle>,
 }
 
-pub fn update(ctx: Context<Update>, price: u64) -> Result<()> {
-    ctx.accounts.oracle.price = price;
+pub fn update(ctx: Context<Update>, price: u64, confidence: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.price = price;
+    oracle.confidence = confidence;
+    oracle.last_update_slot = clock.slot;
+    oracle.last_update_ts = clock.unix_timestamp;
     Ok(())
 }
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 
+pub mod error;
 pub mod instructions;
 pub mod state;
 
@@ -10,13 +11,47 @@ pub mod oracle {
     pub use super::instructions::*;
     use super::*;
 
-    pub fn init(ctx: Context<Init>, price: u64) -> Result<()> {
-        instructions::init(ctx, price)?;
+    pub fn init(
+        ctx: Context<Init>,
+        feed_id: u64,
+        price: u64,
+        exponent: i32,
+    ) -> Result<()> {
+        instructions::init(ctx, feed_id, price, exponent)?;
         Ok(())
     }
 
-    pub fn update(ctx: Context<Update>, price: u64) -> Result<()> {
-        instructions::update(ctx, price)?;
+    pub fn update(
+        ctx: Context<Update>,
+        price: u64,
+        confidence: u64,
+    ) -> Result<()> {
+        instructions::update(ctx, price, confidence)?;
         Ok(())
     }
+
+    pub fn propose_owner(
+        ctx: Context<ProposeOwner>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_owner(ctx, new_owner)?;
+        Ok(())
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        instructions::accept_owner(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_halted(ctx: Context<SetHalted>, halted: bool) -> Result<()> {
+        instructions::set_halted(ctx, halted)?;
+        Ok(())
+    }
+
+    pub fn get_price(
+        ctx: Context<GetPrice>,
+        max_age: u64,
+    ) -> Result<state::Price> {
+        instructions::get_price(ctx, max_age)
+    }
 }
@@ -1,9 +1,92 @@
 use anchor_lang::prelude::*;
 
+use crate::error;
+
 #[account]
 #[derive(InitSpace)]
 // InitSpace - automatically calculates the space needed, Oracle::INIT_SPACE
 pub struct Oracle {
     pub owner: Pubkey,
+    // PDA seed with the owner that created the oracle,
+    // see Oracle::SEED_PREFIX
+    pub feed_id: u64,
+    // Proposed by owner, becomes owner when it accepts.
+    // Pubkey::default() if there is no proposal.
+    pub pending_owner: Pubkey,
+    pub price: u64,
+    // Real price is expected in price +/- confidence
+    pub confidence: u64,
+    // Real price = price * 10^exponent
+    pub exponent: i32,
+    // Set by owner, consumers reject the price while halted
+    pub halted: bool,
+    // Slot and unix timestamp of the last update, checked by consumers
+    pub last_update_slot: u64,
+    pub last_update_ts: i64,
+}
+
+// Returned by get_price
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    // Real price = price * 10^exponent
     pub price: u64,
+    // Real price is expected in price +/- confidence
+    pub confidence: u64,
+    pub exponent: i32,
+    // Unix timestamp of the last update
+    pub timestamp: i64,
+}
+
+impl Oracle {
+    pub const SEED_PREFIX: &'static [u8] = b"oracle";
+
+    // Price if it was updated at most max_age seconds before now
+    pub fn get_price_no_older_than(
+        &self,
+        now: i64,
+        max_age: u64,
+    ) -> Result<Price> {
+        // Check oracle is not halted
+        require!(!self.halted, error::Error::Halted);
+        // Check price is fresh
+        require!(
+            now.saturating_sub(self.last_update_ts) as u64 <= max_age,
+            error::Error::StalePrice
+        );
+
+        Ok(Price {
+            price: self.price,
+            confidence: self.confidence,
+            exponent: self.exponent,
+            timestamp: self.last_update_ts,
+        })
+    }
+}
+
+impl Price {
+    // Value of amount of a token with decimals, with value_decimals decimals.
+    // Valued at the lower bound of the price, price - confidence, rounded
+    // down. None on overflow.
+    pub fn get_value(
+        &self,
+        amount: u64,
+        decimals: u8,
+        value_decimals: u8,
+    ) -> Option<u64> {
+        let p = self.price.saturating_sub(self.confidence) as u128;
+
+        // amount / 10^decimals * p * 10^exponent * 10^value_decimals
+        let exp = value_decimals as i32 + self.exponent - decimals as i32;
+        let value = (amount as u128).checked_mul(p)?;
+        let value = if exp >= 0 {
+            value.checked_mul(10u128.checked_pow(exp as u32)?)?
+        } else {
+            // 0 if 10^-exp overflows
+            10u128
+                .checked_pow(exp.unsigned_abs())
+                .map_or(0, |d| value / d)
+        };
+
+        value.try_into().ok()
+    }
 }
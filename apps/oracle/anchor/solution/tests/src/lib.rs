@@ -1,2 +1,4 @@
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod test_consumer;
@@ -11,6 +11,18 @@ use anchor_client::{
     Client, Cluster,
 };
 
+fn get_oracle_pda(owner: &Pubkey, feed_id: u64) -> Pubkey {
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            oracle::state::Oracle::SEED_PREFIX,
+            owner.as_ref(),
+            &feed_id.to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    pda
+}
+
 #[test]
 fn test() {
     let program_id = oracle::ID;
@@ -25,7 +37,9 @@ fn test() {
     let program = client.program(program_id).unwrap();
 
     // Initialize
-    let oracle_account = Keypair::new();
+    let feed_id = 1;
+    let oracle_account = get_oracle_pda(&payer.pubkey(), feed_id);
+    let attacker = Keypair::new();
 
     let price: u64 = 123;
 
@@ -34,20 +48,26 @@ fn test() {
         .accounts(oracle::accounts::Init {
             payer: payer.pubkey(),
             owner: payer.pubkey(),
-            oracle: oracle_account.pubkey(),
+            oracle: oracle_account,
             system_program: system_program::ID,
         })
         .signer(&payer)
-        .signer(&oracle_account)
-        .args(oracle::instruction::Init { price })
+        .args(oracle::instruction::Init {
+            feed_id,
+            price,
+            exponent: -6,
+        })
         .send()
         .unwrap();
 
     let oracle_state: oracle::state::Oracle =
-        program.account(oracle_account.pubkey()).unwrap();
+        program.account(oracle_account).unwrap();
 
     assert_eq!(oracle_state.owner, payer.pubkey(), "oracle.owner");
+    assert_eq!(oracle_state.feed_id, feed_id, "oracle.feed_id");
     assert_eq!(oracle_state.price, price, "oracle.price");
+    assert_eq!(oracle_state.exponent, -6, "oracle.exponent");
+    assert!(oracle_state.last_update_ts > 0, "oracle.last_update_ts");
 
     // Cannot re-initialize
     let res = program
@@ -55,12 +75,15 @@ fn test() {
         .accounts(oracle::accounts::Init {
             payer: payer.pubkey(),
             owner: payer.pubkey(),
-            oracle: oracle_account.pubkey(),
+            oracle: oracle_account,
             system_program: system_program::ID,
         })
         .signer(&payer)
-        .signer(&oracle_account)
-        .args(oracle::instruction::Init { price })
+        .args(oracle::instruction::Init {
+            feed_id,
+            price,
+            exponent: -6,
+        })
         .send();
 
     assert!(res.is_err(), "re-initialize");
@@ -72,30 +95,179 @@ fn test() {
         .request()
         .accounts(oracle::accounts::Update {
             owner: payer.pubkey(),
-            oracle: oracle_account.pubkey(),
+            oracle: oracle_account,
         })
         .signer(&payer)
-        .args(oracle::instruction::Update { price })
+        .args(oracle::instruction::Update {
+            price,
+            confidence: 12,
+        })
         .send();
 
     assert!(res.is_ok(), "update");
 
     let oracle_state: oracle::state::Oracle =
-        program.account(oracle_account.pubkey()).unwrap();
+        program.account(oracle_account).unwrap();
 
     assert_eq!(oracle_state.owner, payer.pubkey());
     assert_eq!(oracle_state.price, price);
+    assert_eq!(oracle_state.confidence, 12);
 
     // Update - not authorized
     let res = program
         .request()
         .accounts(oracle::accounts::Update {
-            owner: oracle_account.pubkey(),
-            oracle: oracle_account.pubkey(),
+            owner: attacker.pubkey(),
+            oracle: oracle_account,
+        })
+        .signer(&attacker)
+        .args(oracle::instruction::Update {
+            price,
+            confidence: 0,
         })
-        .signer(&oracle_account)
-        .args(oracle::instruction::Update { price })
         .send();
 
     assert!(res.is_err(), "update - not authorized");
+
+    // Halt
+    let set_halted = |owner: &Keypair, halted: bool| {
+        program
+            .request()
+            .accounts(oracle::accounts::SetHalted {
+                owner: owner.pubkey(),
+                oracle: oracle_account,
+            })
+            .signer(owner)
+            .args(oracle::instruction::SetHalted { halted })
+            .send()
+    };
+
+    assert!(
+        set_halted(&attacker, true).is_err(),
+        "set halted - not authorized"
+    );
+    assert!(set_halted(&payer, true).is_ok(), "set halted");
+
+    let oracle_state: oracle::state::Oracle =
+        program.account(oracle_account).unwrap();
+    assert!(oracle_state.halted);
+}
+
+#[test]
+fn test_owner() {
+    let program_id = oracle::ID;
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let client = Client::new_with_options(
+        Cluster::Localnet,
+        &payer,
+        CommitmentConfig::confirmed(),
+    );
+    let program = client.program(program_id).unwrap();
+
+    let feed_id = 2;
+    let oracle_account = get_oracle_pda(&payer.pubkey(), feed_id);
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+
+    // Initialize
+    program
+        .request()
+        .accounts(oracle::accounts::Init {
+            payer: payer.pubkey(),
+            owner: payer.pubkey(),
+            oracle: oracle_account,
+            system_program: system_program::ID,
+        })
+        .signer(&payer)
+        .args(oracle::instruction::Init {
+            feed_id,
+            price: 1,
+            exponent: -6,
+        })
+        .send()
+        .unwrap();
+
+    let propose = |owner: &Keypair, new_owner: Pubkey| {
+        program
+            .request()
+            .accounts(oracle::accounts::ProposeOwner {
+                owner: owner.pubkey(),
+                oracle: oracle_account,
+            })
+            .signer(owner)
+            .args(oracle::instruction::ProposeOwner { new_owner })
+            .send()
+    };
+    let accept = |new_owner: &Keypair| {
+        program
+            .request()
+            .accounts(oracle::accounts::AcceptOwner {
+                new_owner: new_owner.pubkey(),
+                oracle: oracle_account,
+            })
+            .signer(new_owner)
+            .args(oracle::instruction::AcceptOwner {})
+            .send()
+    };
+    let get_oracle = || -> oracle::state::Oracle {
+        program.account(oracle_account).unwrap()
+    };
+
+    // Propose - not authorized
+    assert!(
+        propose(&alice, alice.pubkey()).is_err(),
+        "propose - not owner"
+    );
+
+    // Accept - no proposal
+    assert!(accept(&alice).is_err(), "accept - no proposal");
+
+    // Propose alice
+    assert!(propose(&payer, alice.pubkey()).is_ok(), "propose");
+
+    let oracle_state = get_oracle();
+    assert_eq!(oracle_state.owner, payer.pubkey());
+    assert_eq!(oracle_state.pending_owner, alice.pubkey());
+
+    // Accept - not pending owner
+    assert!(accept(&bob).is_err(), "accept - not pending owner");
+
+    // Overwrite with bob
+    assert!(propose(&payer, bob.pubkey()).is_ok(), "propose - overwrite");
+    assert_eq!(get_oracle().pending_owner, bob.pubkey());
+
+    assert!(accept(&alice).is_err(), "accept - overwritten");
+
+    // Bob accepts
+    assert!(accept(&bob).is_ok(), "accept");
+
+    let oracle_state = get_oracle();
+    assert_eq!(oracle_state.owner, bob.pubkey());
+    assert_eq!(oracle_state.pending_owner, Pubkey::default());
+
+    // Old owner can't update
+    let res = program
+        .request()
+        .accounts(oracle::accounts::Update {
+            owner: payer.pubkey(),
+            oracle: oracle_account,
+        })
+        .signer(&payer)
+        .args(oracle::instruction::Update {
+            price: 2,
+            confidence: 0,
+        })
+        .send();
+
+    assert!(res.is_err(), "update - old owner");
+
+    // Bob proposes alice, then cancels
+    assert!(propose(&bob, alice.pubkey()).is_ok(), "propose");
+    assert!(propose(&bob, Pubkey::default()).is_ok(), "propose - cancel");
+    assert_eq!(get_oracle().pending_owner, Pubkey::default());
+
+    assert!(accept(&alice).is_err(), "accept - cancelled");
+    assert_eq!(get_oracle().owner, bob.pubkey());
 }
@@ -0,0 +1,276 @@
+// Consumer tests run on LiteSVM to set the clock and write oracle accounts.
+// Build the programs with anchor build first.
+use anchor_client::anchor_lang::{
+    AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_client::solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+
+use oracle::state::Oracle;
+
+fn setup(svm: &mut LiteSVM) -> Keypair {
+    svm.add_program_from_file(oracle::ID, "../target/deploy/oracle.so")
+        .unwrap();
+    svm.add_program_from_file(consumer::ID, "../target/deploy/consumer.so")
+        .unwrap();
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    payer
+}
+
+fn set_time(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+// $2.5 +/- $0.1 updated at 990
+fn create_oracle() -> Oracle {
+    Oracle {
+        owner: consumer::ORACLE_OWNER,
+        feed_id: consumer::FEED_ID,
+        pending_owner: Pubkey::default(),
+        price: 2_500_000,
+        confidence: 100_000,
+        exponent: -6,
+        halted: false,
+        last_update_slot: 0,
+        last_update_ts: 990,
+    }
+}
+
+// Write oracle with its discriminator, owned by owner
+fn set_oracle(svm: &mut LiteSVM, key: Pubkey, owner: Pubkey, oracle: &Oracle) {
+    let mut data = vec![];
+    oracle.try_serialize(&mut data).unwrap();
+
+    svm.set_account(
+        key,
+        Account {
+            lamports: 10_000_000,
+            owner,
+            data,
+            ..Account::default()
+        },
+    )
+    .unwrap();
+}
+
+fn send(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    ix: Instruction,
+) -> TransactionResult {
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ));
+    svm.expire_blockhash();
+    res
+}
+
+// 3 tokens with 9 decimals
+fn get_value(oracle: Pubkey, max_age: u64) -> Instruction {
+    Instruction {
+        program_id: consumer::ID,
+        accounts: consumer::accounts::GetValue { oracle }
+            .to_account_metas(None),
+        data: consumer::instruction::GetValue {
+            amount: 3_000_000_000,
+            decimals: 9,
+            max_age,
+        }
+        .data(),
+    }
+}
+
+// Same as get_value, price read through CPI to oracle_program
+fn get_value_cpi(
+    oracle_program: Pubkey,
+    oracle: Pubkey,
+    max_age: u64,
+) -> Instruction {
+    Instruction {
+        program_id: consumer::ID,
+        accounts: consumer::accounts::GetValueCpi {
+            oracle,
+            oracle_program,
+        }
+        .to_account_metas(None),
+        data: consumer::instruction::GetValueCpi {
+            amount: 3_000_000_000,
+            decimals: 9,
+            max_age,
+        }
+        .data(),
+    }
+}
+
+// Check that transaction failed with err
+fn assert_error(res: TransactionResult, err: u32) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => {
+            assert_eq!(e, InstructionError::Custom(err))
+        }
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn test_consumer() {
+    let mut svm = LiteSVM::new();
+    let payer = setup(&mut svm);
+
+    set_time(&mut svm, 1000);
+
+    let oracle_state = create_oracle();
+    let oracle = consumer::get_oracle_pda();
+    set_oracle(&mut svm, oracle, oracle::ID, &oracle_state);
+
+    // Both paths value 3 tokens at $2.4, price - confidence
+    for ix in [get_value(oracle, 10), get_value_cpi(oracle::ID, oracle, 10)] {
+        let res = send(&mut svm, &payer, ix).unwrap();
+        assert_eq!(res.return_data.program_id, consumer::ID);
+        let value =
+            u64::from_le_bytes(res.return_data.data.try_into().unwrap());
+        assert_eq!(value, 7_200_000);
+    }
+
+    // Stale
+    for ix in [get_value(oracle, 9), get_value_cpi(oracle::ID, oracle, 9)] {
+        assert_error(
+            send(&mut svm, &payer, ix),
+            oracle::error::Error::StalePrice.into(),
+        );
+    }
+
+    // Halted
+    let mut halted = oracle_state.clone();
+    halted.halted = true;
+    set_oracle(&mut svm, oracle, oracle::ID, &halted);
+
+    for ix in [get_value(oracle, 10), get_value_cpi(oracle::ID, oracle, 10)] {
+        assert_error(
+            send(&mut svm, &payer, ix),
+            oracle::error::Error::Halted.into(),
+        );
+    }
+}
+
+#[test]
+fn test_consumer_spoof() {
+    let mut svm = LiteSVM::new();
+    let payer = setup(&mut svm);
+
+    set_time(&mut svm, 1000);
+
+    // Valid Oracle with the discriminator and a fake price, owned by another
+    // program
+    let mut fake_state = create_oracle();
+    fake_state.price = 1_000_000_000;
+    fake_state.confidence = 0;
+    let oracle = consumer::get_oracle_pda();
+    set_oracle(&mut svm, oracle, Pubkey::new_unique(), &fake_state);
+
+    for ix in [get_value(oracle, 10), get_value_cpi(oracle::ID, oracle, 10)] {
+        assert_error(
+            send(&mut svm, &payer, ix),
+            anchor_client::anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+                .into(),
+        );
+    }
+
+    // Another program returning a price, here the consumer itself
+    set_oracle(&mut svm, oracle, oracle::ID, &fake_state);
+
+    assert_error(
+        send(&mut svm, &payer, get_value_cpi(consumer::ID, oracle, 10)),
+        anchor_client::anchor_lang::error::ErrorCode::InvalidProgramId.into(),
+    );
+
+    // Oracle of another feed
+    let (fake_oracle, _) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            consumer::ORACLE_OWNER.as_ref(),
+            &(consumer::FEED_ID + 1).to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    fake_state.feed_id = consumer::FEED_ID + 1;
+    set_oracle(&mut svm, fake_oracle, oracle::ID, &fake_state);
+
+    for ix in [
+        get_value(fake_oracle, 10),
+        get_value_cpi(oracle::ID, fake_oracle, 10),
+    ] {
+        assert_error(
+            send(&mut svm, &payer, ix),
+            anchor_client::anchor_lang::error::ErrorCode::ConstraintSeeds
+                .into(),
+        );
+    }
+}
+
+#[test]
+fn test_consumer_squatted_feed() {
+    let mut svm = LiteSVM::new();
+    let payer = setup(&mut svm);
+
+    set_time(&mut svm, 1000);
+
+    // Attacker creates an oracle of FEED_ID with a fake price
+    let attacker = Keypair::new();
+    svm.airdrop(&attacker.pubkey(), 1_000_000_000).unwrap();
+
+    let (squatted, _) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            attacker.pubkey().as_ref(),
+            &consumer::FEED_ID.to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    let ix = Instruction {
+        program_id: oracle::ID,
+        accounts: oracle::accounts::Init {
+            payer: attacker.pubkey(),
+            owner: attacker.pubkey(),
+            oracle: squatted,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: oracle::instruction::Init {
+            feed_id: consumer::FEED_ID,
+            price: 1_000_000_000,
+            exponent: -6,
+        }
+        .data(),
+    };
+    assert!(send(&mut svm, &attacker, ix).is_ok());
+    assert_ne!(squatted, consumer::get_oracle_pda());
+
+    // Fresh price of FEED_ID, not created by ORACLE_OWNER
+    for ix in [
+        get_value(squatted, 10),
+        get_value_cpi(oracle::ID, squatted, 10),
+    ] {
+        assert_error(
+            send(&mut svm, &payer, ix),
+            anchor_client::anchor_lang::error::ErrorCode::ConstraintSeeds
+                .into(),
+        );
+    }
+}
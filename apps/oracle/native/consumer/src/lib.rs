@@ -0,0 +1,155 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    declare_id,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use oracle::price::{Price, get_price_no_older_than, read_price};
+use oracle::state::Oracle;
+
+// Example of a program reading prices from the oracle program
+declare_id!("9gFiJHZvxopVG3x2EQ5ezt7YsHf61i8CMoVS3MExcWNp");
+
+// Decimals of collateral value
+pub const USD_DECIMALS: u8 = 6;
+
+// Feed of the collateral price
+pub const FEED_ID: u64 = 1;
+
+// Key that created the oracle of FEED_ID. Anyone can create an oracle of
+// FEED_ID under their own key, only the oracle PDA of this key is trusted.
+pub const ORACLE_OWNER: Pubkey =
+    pubkey!("48LQzExHeNYHWmtWSghQK9gsNW8rf8Wwp8LTzNdAJJJC");
+
+// Oracle PDA of FEED_ID created by ORACLE_OWNER
+pub fn get_oracle_pda() -> Pubkey {
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            ORACLE_OWNER.as_ref(),
+            &FEED_ID.to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    pda
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum Cmd {
+    // (amount, decimals, max_age)
+    // Value in USD of amount of a collateral token with decimals, priced by
+    // an oracle updated at most max_age seconds ago. Returned as u64.
+    GetValue(u64, u8, u64),
+    // (amount, decimals, max_age)
+    // Same as GetValue, the price is read through CPI to GetPrice of the
+    // oracle program
+    GetValueCpi(u64, u8, u64),
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let ix = Cmd::try_from_slice(instruction_data)?;
+
+    match ix {
+        Cmd::GetValue(amount, decimals, max_age) => {
+            get_value(accounts, amount, decimals, max_age)?;
+        }
+        Cmd::GetValueCpi(amount, decimals, max_age) => {
+            get_value_cpi(accounts, amount, decimals, max_age)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn get_value(
+    accounts: &[AccountInfo],
+    amount: u64,
+    decimals: u8,
+    max_age: u64,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by the oracle program.
+    // Anyone can create an account with the same data owned by another
+    // program and an arbitrary price.
+    if *oracle_account.owner != oracle::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    // Check oracle_account is the oracle of FEED_ID created by ORACLE_OWNER.
+    // Any oracle account owned by the oracle program has a valid layout,
+    // but its price is only as trusted as its owner and publishers.
+    if *oracle_account.key != get_oracle_pda() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Fails if the price is stale or the oracle is halted
+    let price = get_price_no_older_than(oracle_account, max_age)?;
+
+    return_value(&price, amount, decimals)
+}
+
+pub fn get_value_cpi(
+    accounts: &[AccountInfo],
+    amount: u64,
+    decimals: u8,
+    max_age: u64,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_program = next_account_info(account_iter)?;
+    let oracle_account = next_account_info(account_iter)?;
+
+    // Check oracle_program is the oracle program.
+    // Any program can set return data in the layout of Price.
+    if *oracle_program.key != oracle::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // Check oracle_account is the oracle of FEED_ID created by ORACLE_OWNER
+    if *oracle_account.key != get_oracle_pda() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Oracle program checks it owns oracle_account, fails if the price is
+    // stale or the oracle is halted
+    let ix = Instruction::new_with_borsh(
+        oracle::ID,
+        &oracle::Cmd::GetPrice(max_age),
+        vec![AccountMeta::new_readonly(*oracle_account.key, false)],
+    );
+    invoke(&ix, &[oracle_account.clone(), oracle_program.clone()])?;
+
+    // Fails unless return data was set by the oracle program
+    let price = read_price(&oracle::ID)?;
+
+    return_value(&price, amount, decimals)
+}
+
+// Collateral is valued at the lower bound of the price, price - confidence
+fn return_value(
+    price: &Price,
+    amount: u64,
+    decimals: u8,
+) -> Result<(), ProgramError> {
+    let value = price
+        .get_value(amount, decimals, USD_DECIMALS)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    msg!("Collateral value: {}", value);
+    set_return_data(&value.to_le_bytes());
+
+    Ok(())
+}
@@ -0,0 +1,388 @@
+// TransactionResult of litesvm has a large FailedTransactionMetadata error
+#![allow(clippy::result_large_err)]
+
+use litesvm::{LiteSVM, types::TransactionResult};
+use solana_program::sysvar::clock::Clock;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::program as system_program;
+
+use consumer::Cmd;
+use oracle::{error::OracleError, instructions::InitParams, state::Oracle};
+
+fn setup(svm: &mut LiteSVM) {
+    svm.add_program_from_file(consumer::ID, "target/deploy/consumer.so")
+        .unwrap();
+    svm.add_program_from_file(
+        oracle::ID,
+        "../solution/target/deploy/oracle.so",
+    )
+    .unwrap();
+}
+
+fn set_time(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+// Write oracle account owned by owner, the oracle program unless spoofed
+fn set_oracle(svm: &mut LiteSVM, key: Pubkey, owner: Pubkey, oracle: &Oracle) {
+    svm.set_account(
+        key,
+        Account {
+            lamports: 10_000_000,
+            owner,
+            data: borsh::to_vec(oracle).unwrap(),
+            ..Account::default()
+        },
+    )
+    .unwrap();
+}
+
+fn create_oracle(
+    price: u64,
+    confidence: u64,
+    exponent: i32,
+    ts: u64,
+) -> Oracle {
+    let mut oracle = Oracle::load(&[0; Oracle::SPACE]).unwrap();
+    oracle.owner = consumer::ORACLE_OWNER;
+    oracle.max_age = 60;
+    oracle.price = price;
+    oracle.confidence = confidence;
+    oracle.exponent = exponent;
+    oracle.last_update_ts = ts;
+    oracle
+}
+
+fn get_value(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    oracle: Pubkey,
+    amount: u64,
+    decimals: u8,
+    max_age: u64,
+) -> TransactionResult {
+    let ix = Instruction {
+        program_id: consumer::ID,
+        accounts: vec![AccountMeta::new_readonly(oracle, false)],
+        data: borsh::to_vec(&Cmd::GetValue(amount, decimals, max_age)).unwrap(),
+    };
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ));
+    svm.expire_blockhash();
+    res
+}
+
+// GetValueCpi with the price read from oracle_program
+fn get_value_cpi(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    oracle_program: Pubkey,
+    oracle: Pubkey,
+    amount: u64,
+    decimals: u8,
+    max_age: u64,
+) -> TransactionResult {
+    let ix = Instruction {
+        program_id: consumer::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(oracle_program, false),
+            AccountMeta::new_readonly(oracle, false),
+        ],
+        data: borsh::to_vec(&Cmd::GetValueCpi(amount, decimals, max_age))
+            .unwrap(),
+    };
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ));
+    svm.expire_blockhash();
+    res
+}
+
+// Check that transaction failed with err
+fn assert_error(res: TransactionResult, err: InstructionError) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => assert_eq!(e, err),
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn test() {
+    let mut svm = LiteSVM::new();
+    setup(&mut svm);
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    set_time(&mut svm, 1000);
+
+    // $2.5 +/- $0.1
+    let oracle_state = create_oracle(2_500_000, 100_000, -6, 990);
+    let oracle = consumer::get_oracle_pda();
+    set_oracle(&mut svm, oracle, oracle::ID, &oracle_state);
+
+    // 3 tokens with 9 decimals at $2.4
+    let res =
+        get_value(&mut svm, &payer, oracle, 3_000_000_000, 9, 10).unwrap();
+    assert_eq!(res.return_data.program_id, consumer::ID);
+    let value = u64::from_le_bytes(res.return_data.data.try_into().unwrap());
+    assert_eq!(value, 7_200_000);
+
+    // Stale
+    assert_error(
+        get_value(&mut svm, &payer, oracle, 3_000_000_000, 9, 9),
+        InstructionError::Custom(OracleError::StalePrice as u32),
+    );
+
+    // Halted
+    let mut halted = oracle_state.clone();
+    halted.halted = true;
+    set_oracle(&mut svm, oracle, oracle::ID, &halted);
+
+    assert_error(
+        get_value(&mut svm, &payer, oracle, 3_000_000_000, 9, 10),
+        InstructionError::Custom(OracleError::Halted as u32),
+    );
+}
+
+#[test]
+fn test_spoof() {
+    let mut svm = LiteSVM::new();
+    setup(&mut svm);
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    set_time(&mut svm, 1000);
+
+    // Same layout with a fake price, owned by another program
+    let fake_state = create_oracle(1_000_000_000, 0, -6, 1000);
+    let fake_program = Pubkey::new_unique();
+    let fake_oracle = Pubkey::new_unique();
+    set_oracle(&mut svm, fake_oracle, fake_program, &fake_state);
+
+    assert_error(
+        get_value(&mut svm, &payer, fake_oracle, 1, 0, 10),
+        InstructionError::IllegalOwner,
+    );
+
+    // Owned by the consumer program
+    set_oracle(&mut svm, fake_oracle, consumer::ID, &fake_state);
+
+    assert_error(
+        get_value(&mut svm, &payer, fake_oracle, 1, 0, 10),
+        InstructionError::IllegalOwner,
+    );
+
+    // Owned by the system program
+    set_oracle(&mut svm, fake_oracle, Pubkey::default(), &fake_state);
+
+    assert_error(
+        get_value(&mut svm, &payer, fake_oracle, 1, 0, 10),
+        InstructionError::IllegalOwner,
+    );
+
+    // Oracle created by an attacker, owned by the oracle program
+    set_oracle(&mut svm, fake_oracle, oracle::ID, &fake_state);
+
+    assert_error(
+        get_value(&mut svm, &payer, fake_oracle, 1, 0, 10),
+        InstructionError::InvalidSeeds,
+    );
+
+    // Oracle PDA of another feed
+    let (other_feed, _) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            consumer::ORACLE_OWNER.as_ref(),
+            &(consumer::FEED_ID + 1).to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    set_oracle(&mut svm, other_feed, oracle::ID, &fake_state);
+
+    assert_error(
+        get_value(&mut svm, &payer, other_feed, 1, 0, 10),
+        InstructionError::InvalidSeeds,
+    );
+}
+
+#[test]
+fn test_squatted_feed() {
+    let mut svm = LiteSVM::new();
+    setup(&mut svm);
+
+    let attacker = Keypair::new();
+    svm.airdrop(&attacker.pubkey(), 1_000_000_000).unwrap();
+
+    set_time(&mut svm, 1000);
+
+    let send = |svm: &mut LiteSVM, ix: Instruction| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&attacker.pubkey()),
+            &[&attacker],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+
+    // Attacker creates an oracle of FEED_ID and publishes a fake price
+    let (squatted, bump) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            attacker.pubkey().as_ref(),
+            &consumer::FEED_ID.to_le_bytes(),
+        ],
+        &oracle::ID,
+    );
+    let ix = Instruction {
+        program_id: oracle::ID,
+        accounts: vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new_readonly(attacker.pubkey(), true),
+            AccountMeta::new(squatted, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&oracle::Cmd::Init(InitParams {
+            max_age: 60,
+            exponent: -6,
+            feed_id: consumer::FEED_ID,
+            bump,
+            history_capacity: 0,
+        }))
+        .unwrap(),
+    };
+    assert!(send(&mut svm, ix).is_ok());
+
+    for cmd in [
+        oracle::Cmd::AddPublisher(attacker.pubkey()),
+        oracle::Cmd::Update(1_000_000_000),
+    ] {
+        let ix = Instruction {
+            program_id: oracle::ID,
+            accounts: vec![
+                AccountMeta::new(squatted, false),
+                AccountMeta::new_readonly(attacker.pubkey(), true),
+            ],
+            data: borsh::to_vec(&cmd).unwrap(),
+        };
+        assert!(send(&mut svm, ix).is_ok());
+    }
+
+    // Oracle of FEED_ID with a fresh price, not created by ORACLE_OWNER
+    assert_ne!(squatted, consumer::get_oracle_pda());
+    assert_error(
+        get_value(&mut svm, &attacker, squatted, 1, 0, 10),
+        InstructionError::InvalidSeeds,
+    );
+    assert_error(
+        get_value_cpi(&mut svm, &attacker, oracle::ID, squatted, 1, 0, 10),
+        InstructionError::InvalidSeeds,
+    );
+}
+
+#[test]
+fn test_cpi() {
+    let mut svm = LiteSVM::new();
+    setup(&mut svm);
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    set_time(&mut svm, 1000);
+
+    // $2.5 +/- $0.1
+    let oracle_state = create_oracle(2_500_000, 100_000, -6, 990);
+    let oracle = consumer::get_oracle_pda();
+    set_oracle(&mut svm, oracle, oracle::ID, &oracle_state);
+
+    // 3 tokens with 9 decimals at $2.4, same as reading the account
+    let res = get_value_cpi(
+        &mut svm,
+        &payer,
+        oracle::ID,
+        oracle,
+        3_000_000_000,
+        9,
+        10,
+    )
+    .unwrap();
+    assert_eq!(res.return_data.program_id, consumer::ID);
+    let value = u64::from_le_bytes(res.return_data.data.try_into().unwrap());
+    assert_eq!(value, 7_200_000);
+
+    // Stale
+    assert_error(
+        get_value_cpi(
+            &mut svm,
+            &payer,
+            oracle::ID,
+            oracle,
+            3_000_000_000,
+            9,
+            9,
+        ),
+        InstructionError::Custom(OracleError::StalePrice as u32),
+    );
+
+    // Halted
+    let mut halted = oracle_state.clone();
+    halted.halted = true;
+    set_oracle(&mut svm, oracle, oracle::ID, &halted);
+
+    assert_error(
+        get_value_cpi(
+            &mut svm,
+            &payer,
+            oracle::ID,
+            oracle,
+            3_000_000_000,
+            9,
+            10,
+        ),
+        InstructionError::Custom(OracleError::Halted as u32),
+    );
+
+    // Oracle created by an attacker, owned by the oracle program
+    let fake_state = create_oracle(1_000_000_000, 0, -6, 1000);
+    let fake_oracle = Pubkey::new_unique();
+    set_oracle(&mut svm, fake_oracle, oracle::ID, &fake_state);
+
+    assert_error(
+        get_value_cpi(&mut svm, &payer, oracle::ID, fake_oracle, 1, 0, 10),
+        InstructionError::InvalidSeeds,
+    );
+
+    // Another program returning a price, here the consumer itself
+    assert_error(
+        get_value_cpi(&mut svm, &payer, consumer::ID, oracle, 1, 0, 10),
+        InstructionError::IncorrectProgramId,
+    );
+
+    // Oracle account owned by another program, rejected by the oracle program
+    set_oracle(&mut svm, oracle, Pubkey::new_unique(), &fake_state);
+
+    assert_error(
+        get_value_cpi(&mut svm, &payer, oracle::ID, oracle, 1, 0, 10),
+        InstructionError::IllegalOwner,
+    );
+}
@@ -3,16 +3,15 @@ use solana_sdk::{
     commitment_config::CommitmentConfig,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{Keypair, Signer, read_keypair_file},
-    signer::keypair::keypair_from_seed,
-    system_instruction,
+    signature::{Signer, read_keypair_file},
     transaction::Transaction,
 };
+use solana_system_interface::program as system_program;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use borsh::BorshDeserialize;
 use oracle::Cmd;
+use oracle::instructions::InitParams;
 use oracle::state::Oracle;
 
 fn main() {
@@ -54,50 +53,54 @@ fn main() {
         println!("Airdrop confirmed");
     }
 
-    // Create Oracle account, owned by the Oracle program if it doesn't exist
-    // let seed = [1u8; 32];
-    // let oracle_account = keypair_from_seed(&seed).unwrap();
-    let oracle_account = Keypair::new();
-
-    // 32 + 8
-    let space = 40;
-    let lamports = client
-        .get_minimum_balance_for_rent_exemption(space)
-        .unwrap();
-
-    let create_account_ix = system_instruction::create_account(
-        &payer.pubkey(),
-        &oracle_account.pubkey(),
-        lamports,
-        space as u64,
+    // Oracle PDA, created by the program on Init.
+    // Use a random feed id so the demo can run more than once.
+    let feed_id: u64 = rand_feed_id();
+    let (oracle_account, bump) = Pubkey::find_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            payer.pubkey().as_ref(),
+            &feed_id.to_le_bytes(),
+        ],
         &program_id,
     );
-
-    let mut tx = Transaction::new_with_payer(
-        &[create_account_ix],
-        Some(&payer.pubkey()),
-    );
-
-    let blockhash = client.get_latest_blockhash().expect("blockhash");
-    tx.sign(&[&payer, &oracle_account], blockhash);
-
-    let res = client.send_and_confirm_transaction(&tx);
-    match res {
-        Ok(_) => println!("Created Oracle account {}", oracle_account.pubkey()),
-        Err(err) => println!("Err creating coutner account: {:#?}", err),
-    }
+    println!("Oracle account {}", oracle_account);
 
     // Initialize
-    let cmd = Cmd::Init(payer.pubkey(), 1);
+    let cmd = Cmd::Init(InitParams {
+        max_age: 60,
+        exponent: -8,
+        feed_id,
+        bump,
+        history_capacity: 10,
+    });
 
     let ix = Instruction::new_with_borsh(
         program_id,
         &cmd,
-        vec![AccountMeta {
-            pubkey: oracle_account.pubkey(),
-            is_signer: false,
-            is_writable: true,
-        }],
+        vec![
+            AccountMeta {
+                pubkey: payer.pubkey(),
+                is_signer: true,
+                is_writable: true,
+            },
+            // Owner
+            AccountMeta {
+                pubkey: payer.pubkey(),
+                is_signer: true,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: oracle_account,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: system_program::ID,
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
     );
 
     let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
@@ -109,15 +112,42 @@ fn main() {
     }
 
     let data = client
-        .get_account_data(&oracle_account.pubkey())
+        .get_account_data(&oracle_account)
         .expect("Failed to fetch account data");
 
-    let oracle_data =
-        Oracle::try_from_slice(&data).expect("Failed to deserialize");
+    let oracle_data = Oracle::load(&data).expect("Failed to deserialize");
 
     println!("oracle.owner: {:?}", oracle_data.owner);
     println!("oracle.price: {:?}", oracle_data.price);
 
+    // Add payer as publisher
+    let cmd = Cmd::AddPublisher(payer.pubkey());
+
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: oracle_account,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: payer.pubkey(),
+                is_signer: true,
+                is_writable: true,
+            },
+        ],
+    );
+
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], client.get_latest_blockhash().unwrap());
+
+    match client.send_and_confirm_transaction(&tx) {
+        Ok(sig) => println!("Transaction signature: {}", sig),
+        Err(err) => eprintln!("Error sending transaction: {}", err),
+    }
+
     // Update
     let cmd = Cmd::Update(2); // set initial price to 0
 
@@ -126,7 +156,7 @@ fn main() {
         &cmd,
         vec![
             AccountMeta {
-                pubkey: oracle_account.pubkey(),
+                pubkey: oracle_account,
                 is_signer: false,
                 is_writable: true,
             },
@@ -147,12 +177,25 @@ fn main() {
     }
 
     let data = client
-        .get_account_data(&oracle_account.pubkey())
+        .get_account_data(&oracle_account)
         .expect("Failed to fetch account data");
 
-    let oracle_data =
-        Oracle::try_from_slice(&data).expect("Failed to deserialize");
+    let oracle_data = Oracle::load(&data).expect("Failed to deserialize");
 
     println!("oracle.owner: {:?}", oracle_data.owner);
     println!("oracle.price: {:?}", oracle_data.price);
+    println!("oracle.confidence: {:?}", oracle_data.confidence);
+    println!("oracle.exponent: {:?}", oracle_data.exponent);
+    println!(
+        "oracle.last_update_slot: {:?}",
+        oracle_data.last_update_slot
+    );
+    println!("oracle.last_update_ts: {:?}", oracle_data.last_update_ts);
+}
+
+fn rand_feed_id() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
 }
@@ -0,0 +1,25 @@
+use solana_program::program_error::ProgramError;
+
+// Returned as ProgramError::Custom(code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OracleError {
+    // Price was last updated more than max_age seconds ago
+    StalePrice = 6000,
+    // Price moved more than max_deviation_bps, cleared by owner with Resume
+    Halted,
+    // Submitted within min_interval of the last aggregate update
+    UpdateTooFrequent,
+    // Missing or malformed ed25519 instruction before UpdateWithSignature
+    InvalidSignature,
+    // Signed report is for another oracle, in the future or too old
+    InvalidReport,
+    // Signed report nonce or timestamp is not newer than the last one
+    ReplayedReport,
+}
+
+impl From<OracleError> for ProgramError {
+    fn from(e: OracleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,194 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::program_error::ProgramError;
+
+use crate::state::Oracle;
+
+// Ring buffer of aggregated prices, stored in the oracle account after
+// Oracle::SPACE bytes of Oracle:
+//   HistoryHeader | PriceEntry * capacity
+// Read and written in place with bytemuck, without deserializing Oracle.
+// Structs are packed so they can be cast from any offset.
+
+#[repr(C, packed)]
+#[derive(Pod, Zeroable, Debug, Clone, Copy)]
+pub struct HistoryHeader {
+    // Max number of entries, 0 if history is disabled
+    pub capacity: u32,
+    // Number of entries stored, <= capacity
+    pub len: u32,
+    // Index of the next entry to write
+    pub head: u32,
+}
+
+#[repr(C, packed)]
+#[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceEntry {
+    pub timestamp: u64,
+    pub price: u64,
+}
+
+pub struct History<'a> {
+    pub header: &'a HistoryHeader,
+    entries: &'a [PriceEntry],
+}
+
+pub struct HistoryMut<'a> {
+    pub header: &'a mut HistoryHeader,
+    entries: &'a mut [PriceEntry],
+}
+
+pub const HEADER_SPACE: usize = size_of::<HistoryHeader>();
+pub const ENTRY_SPACE: usize = size_of::<PriceEntry>();
+// Max size of an account created through CPI
+pub const MAX_SPACE: usize = 10240;
+// Largest capacity with get_space(capacity) <= MAX_SPACE
+pub const MAX_CAPACITY: u32 =
+    ((MAX_SPACE - Oracle::SPACE - HEADER_SPACE) / ENTRY_SPACE) as u32;
+
+// Size of the oracle account with history of capacity entries
+pub fn get_space(capacity: u32) -> usize {
+    Oracle::SPACE + HEADER_SPACE + capacity as usize * ENTRY_SPACE
+}
+
+// Split history stored in data into header and entries
+fn split(data: &[u8]) -> Result<(&[u8], &[u8]), ProgramError> {
+    let data = data
+        .get(Oracle::SPACE..)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if data.len() < HEADER_SPACE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data.split_at(HEADER_SPACE))
+}
+
+fn split_mut(data: &mut [u8]) -> Result<(&mut [u8], &mut [u8]), ProgramError> {
+    let data = data
+        .get_mut(Oracle::SPACE..)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if data.len() < HEADER_SPACE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data.split_at_mut(HEADER_SPACE))
+}
+
+impl<'a> History<'a> {
+    // data is the data of the oracle account
+    pub fn load(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let (header, entries) = split(data)?;
+        let header: &HistoryHeader = bytemuck::try_from_bytes(header)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let entries = entries
+            .get(..header.capacity as usize * ENTRY_SPACE)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let entries = bytemuck::try_cast_slice(entries)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, entries })
+    }
+
+    // Entries from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = PriceEntry> + '_ {
+        let len = self.header.len as usize;
+        let capacity = self.header.capacity as usize;
+        let head = self.header.head as usize;
+        // Oldest entry is at head once the buffer is full
+        let start = if len < capacity { 0 } else { head };
+
+        (0..len).map(move |i| self.entries[(start + i) % capacity])
+    }
+
+    pub fn last(&self) -> Option<PriceEntry> {
+        self.iter().next_back()
+    }
+
+    // Time weighted average price over [now - window, now].
+    // Each price is in effect from its timestamp until the next entry.
+    // None if there is no price in effect during the window.
+    pub fn get_twap(&self, now: u64, window: u64) -> Option<u64> {
+        let start = now.saturating_sub(window);
+        let mut sum: u128 = 0;
+        let mut duration: u64 = 0;
+        let mut end = now;
+
+        // Newest to oldest, end is when the entry stops being in effect
+        for entry in self.iter().rev() {
+            if entry.timestamp > now {
+                continue;
+            }
+            let t = entry.timestamp.max(start);
+            let dt = end - t;
+            sum += entry.price as u128 * dt as u128;
+            duration += dt;
+            end = t;
+
+            if entry.timestamp <= start {
+                break;
+            }
+        }
+
+        if duration == 0 {
+            // window = 0 or every entry in the window is at now
+            return self
+                .iter()
+                .rev()
+                .find(|entry| entry.timestamp <= now)
+                .map(|entry| entry.price);
+        }
+
+        Some((sum / duration as u128) as u64)
+    }
+
+    // Min and max price of entries in [now - window, now]
+    pub fn get_min_max(&self, now: u64, window: u64) -> Option<(u64, u64)> {
+        let start = now.saturating_sub(window);
+
+        self.iter()
+            .filter(|entry| start <= entry.timestamp && entry.timestamp <= now)
+            .fold(None, |acc, entry| {
+                let (min, max) = acc.unwrap_or((entry.price, entry.price));
+                Some((min.min(entry.price), max.max(entry.price)))
+            })
+    }
+}
+
+impl<'a> HistoryMut<'a> {
+    // data is the data of the oracle account
+    pub fn load(data: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (header, entries) = split_mut(data)?;
+        let header: &mut HistoryHeader =
+            bytemuck::try_from_bytes_mut(header)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+        let entries = entries
+            .get_mut(..header.capacity as usize * ENTRY_SPACE)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let entries = bytemuck::try_cast_slice_mut(entries)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, entries })
+    }
+
+    // Append price, overwriting the oldest entry when full.
+    // A price at the same timestamp as the newest entry replaces it.
+    pub fn push(&mut self, timestamp: u64, price: u64) {
+        let capacity = self.header.capacity;
+        if capacity == 0 {
+            return;
+        }
+
+        let entry = PriceEntry { timestamp, price };
+        let head = self.header.head;
+        let len = self.header.len;
+
+        if len > 0 {
+            let last = ((head + capacity - 1) % capacity) as usize;
+            if self.entries[last].timestamp == timestamp {
+                self.entries[last] = entry;
+                return;
+            }
+        }
+
+        self.entries[head as usize] = entry;
+        self.header.head = (head + 1) % capacity;
+        self.header.len = (len + 1).min(capacity);
+    }
+}
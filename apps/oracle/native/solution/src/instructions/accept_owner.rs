@@ -0,0 +1,48 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::state::{Oracle, Publisher};
+
+pub fn accept_owner(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let new_owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !new_owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Check there is a proposal and new_owner is the pending owner
+    if oracle.pending_owner == Pubkey::default()
+        || oracle.pending_owner != *new_owner.key
+    {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    oracle.owner = oracle.pending_owner;
+    oracle.pending_owner = Pubkey::default();
+    // Reports signed by a previous owner can't be replayed
+    oracle.owner_submission = Publisher {
+        key: oracle.owner,
+        nonce: oracle.max_nonce,
+        ..Publisher::default()
+    };
+    oracle.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::state::{Oracle, Publisher};
+
+pub fn add_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    key: Pubkey,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if oracle.owner != *owner.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // Check publisher is not added yet
+    if oracle.find_publisher(&key).is_some() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // Check there is a free slot
+    let n = oracle.num_publishers as usize;
+    if n >= Oracle::MAX_PUBLISHERS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Reports signed before the publisher was removed can't be replayed
+    oracle.publishers[n] = Publisher {
+        key,
+        nonce: oracle.max_nonce,
+        ..Publisher::default()
+    };
+    oracle.num_publishers += 1;
+    oracle.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
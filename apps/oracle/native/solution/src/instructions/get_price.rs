@@ -0,0 +1,32 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::price::get_price_no_older_than;
+
+// Price of oracle_account for programs calling through CPI, returned as
+// price::Price. Fails if the price is stale or the oracle is halted.
+pub fn get_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_age: u64,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let price = get_price_no_older_than(oracle_account, max_age)?;
+
+    msg!("Price: {}, confidence: {}", price.price, price.confidence);
+    set_return_data(&borsh::to_vec(&price)?);
+
+    Ok(())
+}
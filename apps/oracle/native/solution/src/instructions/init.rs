@@ -5,27 +5,105 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-use crate::state::Oracle;
+use super::lib::{create_pda, get_pda};
+use crate::history::{self, HistoryMut};
+use crate::state::{Oracle, Publisher};
+
+// Parameters of Cmd::Init
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InitParams {
+    // Max age of aggregated submissions in seconds
+    pub max_age: u64,
+    // Decimal exponent of price
+    pub exponent: i32,
+    pub feed_id: u64,
+    // Oracle PDA bump
+    pub bump: u8,
+    // Max number of prices kept in history, 0 to disable history
+    pub history_capacity: u32,
+}
 
 pub fn init(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    owner: Pubkey,
-    price: u64,
+    params: InitParams,
 ) -> Result<(), ProgramError> {
+    let InitParams {
+        max_age,
+        exponent,
+        feed_id,
+        bump,
+        history_capacity,
+    } = params;
+
     let account_iter = &mut accounts.iter();
-    let oracle_account = next_account_info(account_iter)?;
+    let payer = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+    let oracle_pda = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+
+    // Owner signs so that nobody else can create the oracle of its feed_id
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
 
-    let mut data = oracle_account.data.borrow_mut();
-    let mut oracle = Oracle::try_from_slice(&data)?;
+    // Check max_age > 0
+    if max_age == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // Check history_capacity <= history::MAX_CAPACITY
+    if history_capacity > history::MAX_CAPACITY {
+        return Err(ProgramError::InvalidArgument);
+    }
 
-    // Check oracle account is not initialized
-    if oracle.owner != Pubkey::default() {
-        return Err(ProgramError::AccountAlreadyInitialized);
+    // Check that oracle_pda matches expected PDA
+    if *oracle_pda.key != get_pda(program_id, owner.key, feed_id, bump)? {
+        return Err(ProgramError::InvalidSeeds);
     }
 
-    oracle.owner = owner;
-    oracle.price = price;
+    // Create PDA account, fails if oracle_pda already exists
+    create_pda(
+        program_id,
+        payer,
+        oracle_pda,
+        sys_program,
+        history::get_space(history_capacity),
+        &[
+            Oracle::SEED_PREFIX,
+            owner.key.as_ref(),
+            &feed_id.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    let owner = *owner.key;
+    let oracle = Oracle {
+        owner,
+        pending_owner: Pubkey::default(),
+        feed_id,
+        max_age,
+        max_deviation_bps: 0,
+        min_interval: 0,
+        halted: false,
+        exponent,
+        num_publishers: 0,
+        publishers: [Publisher::default(); Oracle::MAX_PUBLISHERS],
+        owner_submission: Publisher {
+            key: owner,
+            ..Publisher::default()
+        },
+        price: 0,
+        confidence: 0,
+        last_update_slot: 0,
+        last_update_ts: 0,
+        max_nonce: 0,
+    };
+
+    let mut data = oracle_pda.data.borrow_mut();
     oracle.serialize(&mut &mut data[..])?;
 
+    let history = HistoryMut::load(&mut data)?;
+    history.header.capacity = history_capacity;
+
     Ok(())
 }
@@ -0,0 +1,76 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::{Pubkey, PubkeyError},
+    sysvar::{Sysvar, rent::Rent},
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::state::Oracle;
+
+// Oracle PDA of feed_id created by owner
+pub fn get_pda(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    feed_id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            Oracle::SEED_PREFIX,
+            owner.as_ref(),
+            &feed_id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+// create_account fails if lamports were sent to the PDA address before init,
+// so a funded PDA is topped up to rent exemption, allocated and assigned.
+// Fails if the account already has data or an owner other than the system
+// program.
+pub fn create_pda<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    pda: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(space);
+
+    if pda.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                pda.key,
+                rent,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), pda.clone(), sys_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let top_up = rent.saturating_sub(pda.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, pda.key, top_up),
+            &[payer.clone(), pda.clone(), sys_program.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(pda.key, space as u64),
+        &[pda.clone(), sys_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(pda.key, program_id),
+        &[pda.clone(), sys_program.clone()],
+        &[signer_seeds],
+    )
+}
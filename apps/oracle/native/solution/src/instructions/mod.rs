@@ -1,5 +1,22 @@
+pub mod accept_owner;
+pub mod add_publisher;
+pub mod get_price;
 pub mod init;
+pub mod lib;
+pub mod propose_owner;
+pub mod remove_publisher;
+pub mod resume;
+pub mod set_limits;
 pub mod update;
+pub mod update_with_signature;
 
+pub use accept_owner::*;
+pub use add_publisher::*;
+pub use get_price::*;
 pub use init::*;
+pub use propose_owner::*;
+pub use remove_publisher::*;
+pub use resume::*;
+pub use set_limits::*;
 pub use update::*;
+pub use update_with_signature::*;
@@ -0,0 +1,41 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::state::Oracle;
+
+pub fn propose_owner(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Pubkey::default() to cancel a pending proposal
+    new_owner: Pubkey,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if oracle.owner != *owner.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // Overwrites any pending proposal
+    oracle.pending_owner = new_owner;
+    oracle.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use crate::history::HistoryMut;
+use crate::state::{Oracle, Publisher};
+
+pub fn remove_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    key: Pubkey,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if oracle.owner != *owner.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let i = oracle
+        .find_publisher(&key)
+        .ok_or(ProgramError::InvalidArgument)?;
+
+    // Move the last publisher into the free slot
+    let last = oracle.num_publishers as usize - 1;
+    oracle.publishers[i] = oracle.publishers[last];
+    oracle.publishers[last] = Publisher::default();
+    oracle.num_publishers -= 1;
+
+    // Drop the removed publisher's price from the aggregate. Not checked
+    // against max_deviation_bps, the owner removing a publisher doesn't halt
+    // the oracle.
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+    let aggregate = if oracle.halted {
+        None
+    } else {
+        oracle.get_aggregate(now)
+    };
+    if let Some((price, confidence, _)) = aggregate {
+        oracle.set_aggregate(clock.slot, now, price, confidence);
+    }
+
+    oracle.serialize(&mut &mut data[..])?;
+
+    // Record aggregate in history
+    if aggregate.is_some() {
+        HistoryMut::load(&mut data)?.push(now, oracle.price);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use crate::history::HistoryMut;
+use crate::state::Oracle;
+
+// Clear halted. The submission that halted the oracle was not stored, the
+// aggregate of fresh accepted submissions is stored. To accept a large move,
+// raise max_deviation_bps with SetLimits.
+pub fn resume(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if oracle.owner != *owner.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // Check oracle is halted
+    if !oracle.halted {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    oracle.halted = false;
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+    let aggregate = oracle.get_aggregate(now);
+    if let Some((price, confidence, _)) = aggregate {
+        oracle.set_aggregate(clock.slot, now, price, confidence);
+    }
+
+    oracle.serialize(&mut &mut data[..])?;
+
+    // Record aggregate in history
+    if aggregate.is_some() {
+        HistoryMut::load(&mut data)?.push(now, oracle.price);
+    }
+
+    Ok(())
+}
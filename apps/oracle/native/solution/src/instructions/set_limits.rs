@@ -0,0 +1,43 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::state::Oracle;
+
+pub fn set_limits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Max change of the aggregated price per update, 0 for no limit
+    max_deviation_bps: u64,
+    // Min seconds between 2 submissions of a publisher, 0 for no limit
+    min_interval: u64,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let owner = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if oracle.owner != *owner.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    oracle.max_deviation_bps = max_deviation_bps;
+    oracle.min_interval = min_interval;
+    oracle.serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -1,12 +1,18 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
+    msg,
     program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
 };
 
+use crate::error::OracleError;
+use crate::history::HistoryMut;
 use crate::state::Oracle;
 
 pub fn update(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     price: u64,
 ) -> Result<(), ProgramError> {
@@ -14,19 +20,88 @@ pub fn update(
     let oracle_account = next_account_info(account_iter)?;
     let signer = next_account_info(account_iter)?;
 
-    let mut data = oracle_account.data.borrow_mut();
-    let mut oracle = Oracle::try_from_slice(&data)?;
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
 
     if !signer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    if oracle.owner != *signer.key {
-        return Err(ProgramError::IllegalOwner);
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    submit(oracle_account, signer.key, price, now, None)
+}
+
+// Store price of publisher key at timestamp and update the aggregate.
+// nonce is set for signed reports and must be greater than the nonce of the
+// publisher's last report, or Oracle::max_nonce when it was added.
+// Signed reports of the owner are stored in Oracle::owner_submission.
+pub fn submit(
+    oracle_account: &AccountInfo,
+    key: &Pubkey,
+    price: u64,
+    timestamp: u64,
+    nonce: Option<u64>,
+) -> Result<(), ProgramError> {
+    let mut data = oracle_account.data.borrow_mut();
+    let mut oracle = Oracle::load(&data)?;
+
+    // Check key is a publisher, or the owner for signed reports
+    let i = oracle
+        .find_submitter(key, nonce.is_some())
+        .ok_or(ProgramError::IllegalOwner)?;
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    // Check oracle is not halted
+    if oracle.halted {
+        return Err(OracleError::Halted.into());
+    }
+
+    let mut publisher = *oracle.submitter_mut(i);
+
+    // Check report is newer than the publisher's last submission
+    if let Some(nonce) = nonce {
+        if nonce <= publisher.nonce
+            || (publisher.has_price && timestamp <= publisher.timestamp)
+        {
+            return Err(OracleError::ReplayedReport.into());
+        }
+        publisher.nonce = nonce;
+        oracle.max_nonce = oracle.max_nonce.max(nonce);
     }
 
-    oracle.price = price;
+    // Check min_interval since the last aggregate update, by any submitter
+    if now.saturating_sub(oracle.last_update_ts) < oracle.min_interval {
+        return Err(OracleError::UpdateTooFrequent.into());
+    }
+
+    // Store submission in the publisher's slot
+    let last = publisher;
+    publisher.price = price;
+    publisher.timestamp = timestamp;
+    publisher.has_price = true;
+    *oracle.submitter_mut(i) = publisher;
+
+    // Halts the oracle if the aggregate moved too much. The transaction
+    // succeeds so that halted is stored.
+    let updated = oracle.update_aggregate(clock.slot, now);
+    if oracle.halted {
+        // Drop the submission so that resuming doesn't publish it,
+        // the nonce of a signed report is still used
+        *oracle.submitter_mut(i) = last;
+        msg!("Oracle halted, price {} exceeds max deviation", price);
+    }
     oracle.serialize(&mut &mut data[..])?;
 
+    // Record aggregate in history
+    if updated {
+        HistoryMut::load(&mut data)?.push(now, oracle.price);
+    }
+
     Ok(())
 }
@@ -0,0 +1,70 @@
+use borsh::BorshDeserialize;
+use solana_instructions_sysvar::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::update::submit;
+use crate::error::OracleError;
+use crate::report::{PriceReport, parse_ed25519_ix};
+use crate::state::Oracle;
+
+// Submit a PriceReport, see report::PriceReport. Anyone can send it.
+// The previous instruction must be the ed25519 program verifying the
+// signature of the report, see report::create_ed25519_ix.
+pub fn update_with_signature(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+    let oracle_account = next_account_info(account_iter)?;
+    let ix_sysvar = next_account_info(account_iter)?;
+
+    // Check oracle_account is owned by this program
+    if oracle_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // Load the ed25519 instruction before this one.
+    // Fails if ix_sysvar is not the instructions sysvar.
+    let current = load_current_index_checked(ix_sysvar)? as usize;
+    if current == 0 {
+        return Err(OracleError::InvalidSignature.into());
+    }
+    let ix = load_instruction_at_checked(current - 1, ix_sysvar)?;
+    let (signer, message) = parse_ed25519_ix(&ix)
+        .map_err(|_| ProgramError::from(OracleError::InvalidSignature))?;
+
+    let report = PriceReport::try_from_slice(message)
+        .map_err(|_| ProgramError::from(OracleError::InvalidReport))?;
+
+    // Check report is for this oracle
+    if report.feed != *oracle_account.key {
+        return Err(OracleError::InvalidReport.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    // Check report is not in the future and not older than max_age
+    let max_age = {
+        let data = oracle_account.data.borrow();
+        Oracle::load(&data)?.max_age
+    }; // Drop borrow here
+    if report.timestamp > now || now - report.timestamp > max_age {
+        return Err(OracleError::InvalidReport.into());
+    }
+
+    submit(
+        oracle_account,
+        &signer,
+        report.price,
+        report.timestamp,
+        Some(report.nonce),
+    )
+}
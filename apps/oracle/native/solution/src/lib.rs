@@ -1,33 +1,94 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
+    account_info::AccountInfo, declare_id, entrypoint::ProgramResult,
     pubkey::Pubkey,
 };
 
+pub mod error;
+pub mod history;
 pub mod instructions;
+pub mod price;
+pub mod report;
 pub mod state;
 
+use instructions::InitParams;
+
+// Consumers check that oracle accounts are owned by this program
+declare_id!("D1vahYzQNMZXTMuDoABFkJMhn2pDYfNuJ41Vv9m8VupK");
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub enum Cmd {
-    Init(Pubkey, u64),
+    // Oracle PDA of (owner, feed_id), signed by owner
+    Init(InitParams),
+    // Price submitted by a publisher
     Update(u64),
+    // Publisher to add, signed by owner
+    AddPublisher(Pubkey),
+    // Publisher to remove, signed by owner
+    RemovePublisher(Pubkey),
+    // New owner proposed by owner, Pubkey::default() cancels the proposal
+    ProposeOwner(Pubkey),
+    // Signed by the pending owner
+    AcceptOwner,
+    // (max_deviation_bps, min_interval), signed by owner
+    SetLimits(u64, u64),
+    // Clear halted, signed by owner
+    Resume,
+    // Price report signed by the owner or a publisher, sent by anyone after
+    // the ed25519 instruction verifying it,
+    // see report::create_update_with_signature_ixs
+    UpdateWithSignature,
+    // (max_age) Price for programs calling through CPI, returned as
+    // price::Price, see price::read_price
+    GetPrice(u64),
 }
 
-entrypoint!(process_instruction);
+// Programs depending on this crate enable no-entrypoint
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
 
 pub fn process_instruction(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
     let ix = Cmd::try_from_slice(instruction_data)?;
 
     match ix {
-        Cmd::Init(owner, price) => {
-            instructions::init(accounts, owner, price)?;
+        Cmd::Init(params) => {
+            instructions::init(program_id, accounts, params)?;
         }
         Cmd::Update(price) => {
-            instructions::update(accounts, price)?;
+            instructions::update(program_id, accounts, price)?;
+        }
+        Cmd::AddPublisher(key) => {
+            instructions::add_publisher(program_id, accounts, key)?;
+        }
+        Cmd::RemovePublisher(key) => {
+            instructions::remove_publisher(program_id, accounts, key)?;
+        }
+        Cmd::ProposeOwner(new_owner) => {
+            instructions::propose_owner(program_id, accounts, new_owner)?;
+        }
+        Cmd::AcceptOwner => {
+            instructions::accept_owner(program_id, accounts)?;
+        }
+        Cmd::SetLimits(max_deviation_bps, min_interval) => {
+            instructions::set_limits(
+                program_id,
+                accounts,
+                max_deviation_bps,
+                min_interval,
+            )?;
+        }
+        Cmd::Resume => {
+            instructions::resume(program_id, accounts)?;
+        }
+        Cmd::UpdateWithSignature => {
+            instructions::update_with_signature(program_id, accounts)?;
+        }
+        Cmd::GetPrice(max_age) => {
+            instructions::get_price(program_id, accounts, max_age)?;
         }
     }
 
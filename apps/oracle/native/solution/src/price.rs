@@ -0,0 +1,115 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program::get_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use crate::{error::OracleError, state::Oracle};
+
+// Helpers for programs reading the oracle account
+
+// Returned by Cmd::GetPrice
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub struct Price {
+    // Real price = price * 10^exponent
+    pub price: u64,
+    // Real price is expected in price +/- confidence
+    pub confidence: u64,
+    pub exponent: i32,
+    // Unix timestamp of the last update
+    pub timestamp: u64,
+}
+
+impl Price {
+    // Value of amount of a token with decimals, with value_decimals decimals.
+    // Valued at the lower bound of the price, price - confidence, rounded
+    // down. None on overflow.
+    pub fn get_value(
+        &self,
+        amount: u64,
+        decimals: u8,
+        value_decimals: u8,
+    ) -> Option<u64> {
+        let p = self.price.saturating_sub(self.confidence) as u128;
+
+        // amount / 10^decimals * p * 10^exponent * 10^value_decimals
+        let exp = value_decimals as i32 + self.exponent - decimals as i32;
+        let value = (amount as u128).checked_mul(p)?;
+        let value = if exp >= 0 {
+            value.checked_mul(10u128.checked_pow(exp as u32)?)?
+        } else {
+            // 0 if 10^-exp overflows
+            10u128
+                .checked_pow(exp.unsigned_abs())
+                .map_or(0, |d| value / d)
+        };
+
+        value.try_into().ok()
+    }
+}
+
+impl Oracle {
+    // Price if it was updated at most max_age seconds before now
+    pub fn get_price_no_older_than(
+        &self,
+        now: u64,
+        max_age: u64,
+    ) -> Result<Price, ProgramError> {
+        // Check oracle account is initialized
+        if self.owner == Pubkey::default() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        // Check oracle is not halted
+        if self.halted {
+            return Err(OracleError::Halted.into());
+        }
+        // Check price is fresh
+        if now.saturating_sub(self.last_update_ts) > max_age {
+            return Err(OracleError::StalePrice.into());
+        }
+
+        Ok(Price {
+            price: self.price,
+            confidence: self.confidence,
+            exponent: self.exponent,
+            timestamp: self.last_update_ts,
+        })
+    }
+}
+
+// Price stored in account if it was updated at most max_age seconds ago.
+// Callers must check that account is owned by the oracle program,
+// see crate::ID.
+pub fn get_price_no_older_than(
+    account: &AccountInfo,
+    max_age: u64,
+) -> Result<Price, ProgramError> {
+    let oracle = {
+        let data = account.data.borrow();
+        Oracle::load(&data)?
+    }; // Drop borrow here
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    oracle.get_price_no_older_than(now, max_age)
+}
+
+// Read Price after a CPI to GetPrice of the oracle program program_id
+pub fn read_price(program_id: &Pubkey) -> Result<Price, ProgramError> {
+    let (caller, data) =
+        get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+
+    // Check return data was set by the oracle program, any program called
+    // before can set return data in the layout of Price
+    if caller != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(Price::try_from_slice(&data)?)
+}
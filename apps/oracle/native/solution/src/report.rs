@@ -0,0 +1,145 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+use crate::Cmd;
+
+// Price report signed off-chain by the owner or a publisher and submitted by
+// anyone with UpdateWithSignature. The signature is verified by the ed25519
+// program in the instruction before UpdateWithSignature.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PriceReport {
+    // Oracle account the report is for
+    pub feed: Pubkey,
+    pub price: u64,
+    // Unix timestamp of the price, must be newer than the signer's last
+    pub timestamp: u64,
+    // Must be greater than the nonce of the signer's last report. A newly
+    // added publisher or new owner starts at the highest nonce accepted by
+    // the oracle.
+    pub nonce: u64,
+}
+
+// Layout of ed25519 program instruction data with 1 signature:
+//   num signatures (u8) | padding (u8) | offsets (7 * u16)
+//   | public key (32) | signature (64) | message
+const OFFSETS_START: usize = 2;
+const PUBKEY_OFFSET: usize = OFFSETS_START + 7 * 2;
+const SIGNATURE_OFFSET: usize = PUBKEY_OFFSET + 32;
+const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + 64;
+// Instruction index of data in the ed25519 instruction itself
+const CURRENT_IX: u16 = u16::MAX;
+
+impl PriceReport {
+    // Bytes signed by the owner or publisher
+    pub fn get_message(&self) -> Vec<u8> {
+        borsh::to_vec(self).unwrap()
+    }
+
+    pub fn sign(&self, signer: &dyn Signer) -> [u8; 64] {
+        signer.sign_message(&self.get_message()).into()
+    }
+}
+
+// ed25519 program instruction verifying signature of message by pubkey
+pub fn create_ed25519_ix(
+    pubkey: &Pubkey,
+    signature: &[u8; 64],
+    message: &[u8],
+) -> Instruction {
+    let offsets = [
+        SIGNATURE_OFFSET as u16,
+        CURRENT_IX,
+        PUBKEY_OFFSET as u16,
+        CURRENT_IX,
+        MESSAGE_OFFSET as u16,
+        message.len() as u16,
+        CURRENT_IX,
+    ];
+
+    let mut data = Vec::with_capacity(MESSAGE_OFFSET + message.len());
+    data.extend_from_slice(&[1, 0]);
+    for offset in offsets {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+// Public key and message of an ed25519 program instruction with 1 signature.
+// Only the signature is checked by the ed25519 program, so data must be in
+// the instruction itself and not point to another instruction.
+pub fn parse_ed25519_ix(
+    ix: &Instruction,
+) -> Result<(Pubkey, &[u8]), ProgramError> {
+    if ix.program_id != ed25519_program::ID {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let data = &ix.data;
+    if data.len() < PUBKEY_OFFSET || data[0] != 1 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let offset = |i: usize| {
+        let start = OFFSETS_START + i * 2;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+    let pubkey_offset = offset(2) as usize;
+    let message_offset = offset(4) as usize;
+    let message_size = offset(5) as usize;
+
+    // Check signature, public key and message are in this instruction
+    if offset(1) != CURRENT_IX
+        || offset(3) != CURRENT_IX
+        || offset(6) != CURRENT_IX
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((Pubkey::try_from(pubkey).unwrap(), message))
+}
+
+// Off-chain helper. Instructions for a relayer to submit report signed by
+// signer, send them in this order in a single transaction.
+pub fn create_update_with_signature_ixs(
+    program_id: &Pubkey,
+    report: &PriceReport,
+    signer: &dyn Signer,
+) -> [Instruction; 2] {
+    let signature = report.sign(signer);
+
+    [
+        create_ed25519_ix(&signer.pubkey(), &signature, &report.get_message()),
+        Instruction::new_with_borsh(
+            *program_id,
+            &Cmd::UpdateWithSignature,
+            vec![
+                AccountMeta::new(report.feed, false),
+                AccountMeta::new_readonly(
+                    solana_instructions_sysvar::ID,
+                    false,
+                ),
+            ],
+        ),
+    ]
+}
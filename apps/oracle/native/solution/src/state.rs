@@ -1,8 +1,181 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default)]
+pub struct Publisher {
+    pub key: Pubkey,
+    // Last submitted price
+    pub price: u64,
+    // Unix timestamp of the last submission
+    pub timestamp: u64,
+    // false until the first submission
+    pub has_price: bool,
+    // Nonce of the last signed report, see report::PriceReport.
+    // Starts at Oracle::max_nonce when the publisher is added.
+    pub nonce: u64,
+}
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Oracle {
+    // Adds and removes publishers
     pub owner: Pubkey,
+    // Proposed by owner, becomes owner when it accepts.
+    // Pubkey::default() if there is no proposal.
+    pub pending_owner: Pubkey,
+    // PDA seed with the owner that created the oracle,
+    // see Oracle::SEED_PREFIX
+    pub feed_id: u64,
+    // Submissions older than max_age seconds are not aggregated
+    pub max_age: u64,
+    // Max change of the aggregated price per update in basis points,
+    // the oracle is halted when exceeded. 0 for no limit.
+    pub max_deviation_bps: u64,
+    // Min seconds between 2 aggregate updates, 0 for no limit
+    pub min_interval: u64,
+    // Set when max_deviation_bps is exceeded, cleared by owner.
+    // The submission exceeding it is dropped, updates are rejected and the
+    // price is not stored while halted.
+    pub halted: bool,
+    // Real price = price * 10^exponent
+    pub exponent: i32,
+    pub num_publishers: u8,
+    // publishers[..num_publishers] are in use, each publisher has a slot
+    pub publishers: [Publisher; Oracle::MAX_PUBLISHERS],
+    // Slot of reports signed by the owner, key is the owner. Not used if
+    // the owner is a publisher, its reports go to its publisher slot then.
+    pub owner_submission: Publisher,
+    // Median of fresh submissions at the last update
     pub price: u64,
+    // Half of the spread of fresh submissions at the last update
+    pub confidence: u64,
+    // Slot and unix timestamp of the last update
+    pub last_update_slot: u64,
+    pub last_update_ts: u64,
+    // Highest nonce of signed reports accepted by this oracle. Kept when a
+    // publisher is removed, so that its reports can't be replayed after it
+    // is added again.
+    pub max_nonce: u64,
+}
+
+impl Oracle {
+    // PDA seeds are SEED_PREFIX, owner at init and feed_id
+    pub const SEED_PREFIX: &'static [u8; 6] = b"oracle";
+    pub const MAX_PUBLISHERS: usize = 8;
+    // 32 + 32 + 8 + 8 + 8 + 8 + 1 + 4 + 1 + (32 + 8 + 8 + 1 + 8) * 9
+    // + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: usize = 655;
+
+    // Deserialize Oracle from the data of an oracle account,
+    // which can be followed by history
+    pub fn load(data: &[u8]) -> Result<Oracle, ProgramError> {
+        let data = data
+            .get(..Oracle::SPACE)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Ok(Oracle::try_from_slice(data)?)
+    }
+
+    // Median, confidence and oldest timestamp of submissions at most max_age
+    // old at now. Signed reports can be older than the update storing them.
+    // None if there is no fresh submission
+    pub fn get_aggregate(&self, now: u64) -> Option<(u64, u64, u64)> {
+        let mut prices = [0u64; Self::MAX_PUBLISHERS + 1];
+        let mut n = 0;
+        let mut timestamp = u64::MAX;
+
+        let publishers = &self.publishers[..self.num_publishers as usize];
+        let owner = self.find_publisher(&self.owner).is_none();
+        let owner = owner.then_some(&self.owner_submission);
+
+        for publisher in publishers.iter().chain(owner) {
+            if publisher.has_price
+                && now.saturating_sub(publisher.timestamp) <= self.max_age
+            {
+                prices[n] = publisher.price;
+                n += 1;
+                timestamp = timestamp.min(publisher.timestamp);
+            }
+        }
+
+        if n == 0 {
+            return None;
+        }
+
+        let prices = &mut prices[..n];
+        prices.sort_unstable();
+
+        let median = if n % 2 == 1 {
+            prices[n / 2]
+        } else {
+            // Average of the 2 middle prices, rounded down
+            ((prices[n / 2 - 1] as u128 + prices[n / 2] as u128) / 2) as u64
+        };
+        let conf = (prices[n - 1] - prices[0]) / 2;
+
+        Some((median, conf, timestamp))
+    }
+
+    // Check that price moved at most max_deviation_bps from the stored price
+    pub fn is_within_deviation(&self, price: u64) -> bool {
+        // No limit or no price to compare with
+        if self.max_deviation_bps == 0 || self.price == 0 {
+            return true;
+        }
+        let diff = self.price.abs_diff(price) as u128;
+        diff * 10_000 <= self.max_deviation_bps as u128 * self.price as u128
+    }
+
+    // Store aggregate of fresh submissions, kept if there is none.
+    // Halts the oracle instead if the aggregate moved more than
+    // max_deviation_bps. Returns true if the aggregate was stored.
+    pub fn update_aggregate(&mut self, slot: u64, now: u64) -> bool {
+        if self.halted {
+            return false;
+        }
+        let Some((price, confidence, _)) = self.get_aggregate(now) else {
+            return false;
+        };
+        if !self.is_within_deviation(price) {
+            self.halted = true;
+            return false;
+        }
+        self.set_aggregate(slot, now, price, confidence);
+        true
+    }
+
+    pub fn set_aggregate(
+        &mut self,
+        slot: u64,
+        now: u64,
+        price: u64,
+        confidence: u64,
+    ) {
+        self.price = price;
+        self.confidence = confidence;
+        self.last_update_slot = slot;
+        self.last_update_ts = now;
+    }
+
+    // Index of key in publishers[..num_publishers]
+    pub fn find_publisher(&self, key: &Pubkey) -> Option<usize> {
+        self.publishers[..self.num_publishers as usize]
+            .iter()
+            .position(|publisher| publisher.key == *key)
+    }
+
+    // Index of the slot of key in publishers, or MAX_PUBLISHERS for
+    // owner_submission if key is the owner and owner is true
+    pub fn find_submitter(&self, key: &Pubkey, owner: bool) -> Option<usize> {
+        self.find_publisher(key).or_else(|| {
+            (owner && *key == self.owner).then_some(Self::MAX_PUBLISHERS)
+        })
+    }
+
+    // Slot at index i returned by find_submitter
+    pub fn submitter_mut(&mut self, i: usize) -> &mut Publisher {
+        if i == Self::MAX_PUBLISHERS {
+            &mut self.owner_submission
+        } else {
+            &mut self.publishers[i]
+        }
+    }
 }
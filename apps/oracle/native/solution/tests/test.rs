@@ -1,13 +1,120 @@
+// TransactionResult of litesvm has a large FailedTransactionMetadata error
+#![allow(clippy::result_large_err)]
+
 use borsh::BorshDeserialize;
-use litesvm::LiteSVM;
+use litesvm::{LiteSVM, types::TransactionResult};
+use solana_program::sysvar::clock::Clock;
 use solana_sdk::{
     account::Account,
-    instruction::{AccountMeta, Instruction},
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::program as system_program;
+
+use oracle::{
+    Cmd,
+    error::OracleError,
+    history::{self, History, PriceEntry},
+    instructions::InitParams,
+    price::Price,
+    report::{self, PriceReport},
+    state::Oracle,
 };
 
-use oracle::{Cmd, state::Oracle};
+const FEED_ID: u64 = 1;
+
+fn setup(svm: &mut LiteSVM) -> Pubkey {
+    let program_keypair = Keypair::new();
+    let program_id = program_keypair.pubkey();
+    svm.add_program_from_file(program_id, "target/deploy/oracle.so")
+        .unwrap();
+
+    program_id
+}
+
+fn get_oracle_pda(
+    program_id: Pubkey,
+    owner: &Pubkey,
+    feed_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Oracle::SEED_PREFIX, owner.as_ref(), &feed_id.to_le_bytes()],
+        &program_id,
+    )
+}
+
+fn create_init_ix(
+    program_id: Pubkey,
+    payer: Pubkey,
+    owner: Pubkey,
+    max_age: u64,
+    exponent: i32,
+    feed_id: u64,
+    history_capacity: u32,
+) -> Instruction {
+    let (oracle, bump) = get_oracle_pda(program_id, &owner, feed_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(owner, true),
+            AccountMeta::new(oracle, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&Cmd::Init(InitParams {
+            max_age,
+            exponent,
+            feed_id,
+            bump,
+            history_capacity,
+        }))
+        .unwrap(),
+    }
+}
+
+// Instruction on oracle signed by signer
+fn create_ix(
+    program_id: Pubkey,
+    cmd: &Cmd,
+    oracle: Pubkey,
+    signer: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(oracle, false)];
+    if let Some(signer) = signer {
+        accounts.push(AccountMeta::new(signer, true));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: borsh::to_vec(cmd).unwrap(),
+    }
+}
+
+fn get_oracle(svm: &LiteSVM, oracle: &Pubkey) -> Oracle {
+    let data = svm.get_account(oracle).unwrap().data;
+    Oracle::load(&data).unwrap()
+}
+
+// Check that transaction failed with err
+fn assert_oracle_error(res: TransactionResult, err: OracleError) {
+    match res.unwrap_err().err {
+        TransactionError::InstructionError(_, e) => {
+            assert_eq!(e, InstructionError::Custom(err as u32))
+        }
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+fn set_clock(svm: &mut LiteSVM, slot: u64, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.slot = slot;
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
 
 #[test]
 fn test() {
@@ -15,106 +122,1212 @@ fn test() {
 
     let owner = Keypair::new();
     let attacker = Keypair::new();
-    let oracle = Keypair::new();
-    let program_keypair = Keypair::new();
-    let program_id = program_keypair.pubkey();
-    svm.add_program_from_file(program_id, "target/deploy/oracle.so")
-        .unwrap();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
 
     svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&attacker.pubkey(), 1_000_000_000).unwrap();
 
-    let oracle_account = Account {
-        lamports: 1_000_000,
-        owner: program_id,
-        data: vec![0u8; std::mem::size_of::<Oracle>()],
-        ..Account::default()
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
     };
-    svm.set_account(oracle.pubkey(), oracle_account).unwrap();
 
     // Init
-    let init_ix = Instruction {
+    let max_age = 60;
+    let init_ix = create_init_ix(
         program_id,
-        accounts: vec![AccountMeta::new(oracle.pubkey(), false)],
-        data: borsh::to_vec(&Cmd::Init(owner.pubkey(), 123)).unwrap(),
-    };
-
-    svm.send_transaction(Transaction::new_signed_with_payer(
-        &[init_ix],
-        Some(&owner.pubkey()),
-        &[&owner],
-        svm.latest_blockhash(),
-    ))
-    .unwrap();
+        owner.pubkey(),
+        owner.pubkey(),
+        max_age,
+        -8,
+        FEED_ID,
+        0,
+    );
+    assert!(send(&mut svm, init_ix, &owner).is_ok());
 
-    let data = svm.get_account(&oracle.pubkey()).unwrap().data;
-    let oracle_state = Oracle::try_from_slice(&data).unwrap();
+    let oracle_state = get_oracle(&svm, &oracle);
     assert_eq!(oracle_state.owner, owner.pubkey());
-    assert_eq!(oracle_state.price, 123);
+    assert_eq!(oracle_state.max_age, max_age);
+    assert_eq!(oracle_state.exponent, -8);
+    assert_eq!(oracle_state.num_publishers, 0);
+    assert_eq!(oracle_state.feed_id, FEED_ID);
+
+    // Created by the program with exact space
+    let account = svm.get_account(&oracle).unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.data.len(), history::get_space(0));
+
+    // Init with an account that is not the PDA of feed_id
+    let mut init_ix = create_init_ix(
+        program_id,
+        attacker.pubkey(),
+        attacker.pubkey(),
+        max_age,
+        -8,
+        FEED_ID + 1,
+        0,
+    );
+    init_ix.accounts[2].pubkey = Pubkey::new_unique();
+    assert!(send(&mut svm, init_ix, &attacker).is_err());
 
     // Re-init
-    let init_ix = Instruction {
+    let init_ix = create_init_ix(
         program_id,
-        accounts: vec![AccountMeta::new(oracle.pubkey(), false)],
-        data: borsh::to_vec(&Cmd::Init(owner.pubkey(), 999)).unwrap(),
-    };
+        owner.pubkey(),
+        owner.pubkey(),
+        max_age,
+        -8,
+        FEED_ID,
+        0,
+    );
+    assert!(
+        send(&mut svm, init_ix, &owner).is_err(),
+        "Reinitialization should fail because oracle PDA already exists"
+    );
+
+    // Attacker creates the oracle of FEED_ID for owner without its signature
+    let mut init_ix = create_init_ix(
+        program_id,
+        attacker.pubkey(),
+        owner.pubkey(),
+        max_age,
+        -8,
+        FEED_ID + 1,
+        0,
+    );
+    init_ix.accounts[1].is_signer = false;
+    assert!(
+        send(&mut svm, init_ix, &attacker).is_err(),
+        "Owner must sign init"
+    );
+
+    // Attacker's oracle of FEED_ID is another PDA
+    let init_ix = create_init_ix(
+        program_id,
+        attacker.pubkey(),
+        attacker.pubkey(),
+        max_age,
+        -8,
+        FEED_ID,
+        0,
+    );
+    assert!(send(&mut svm, init_ix, &attacker).is_ok());
+    let (attacker_oracle, _) =
+        get_oracle_pda(program_id, &attacker.pubkey(), FEED_ID);
+    assert_ne!(attacker_oracle, oracle);
+    assert_eq!(get_oracle(&svm, &oracle).owner, owner.pubkey());
+    assert_eq!(get_oracle(&svm, &attacker_oracle).owner, attacker.pubkey());
+
+    // Update by a key that is not a publisher
+    let update_ix =
+        create_ix(program_id, &Cmd::Update(1234), oracle, Some(owner.pubkey()));
+    assert!(send(&mut svm, update_ix, &owner).is_err());
+
+    // Add publisher
+    let add_ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, add_ix.clone(), &owner).is_ok());
+
+    // Same publisher twice
+    assert!(send(&mut svm, add_ix, &owner).is_err());
+
+    // Add publisher by attacker
+    let add_ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(attacker.pubkey()),
+        oracle,
+        Some(attacker.pubkey()),
+    );
+    assert!(
+        send(&mut svm, add_ix, &attacker).is_err(),
+        "Only owner can add publishers"
+    );
 
+    // Update
+    let update_ix =
+        create_ix(program_id, &Cmd::Update(1234), oracle, Some(owner.pubkey()));
+    assert!(send(&mut svm, update_ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 1234);
+    assert_eq!(oracle_state.confidence, 0);
+
+    // Invalid update (by attacker)
+    let update_ix = create_ix(
+        program_id,
+        &Cmd::Update(9999),
+        oracle,
+        Some(attacker.pubkey()),
+    );
+    assert!(
+        send(&mut svm, update_ix, &attacker).is_err(),
+        "Unauthorized signer should not be able to update oracle"
+    );
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 1234);
+
+    // Account not owned by the program
+    let mut fake = get_oracle(&svm, &oracle);
+    fake.owner = attacker.pubkey();
+
+    let fake_oracle = Pubkey::new_unique();
+    svm.set_account(
+        fake_oracle,
+        Account {
+            lamports: 10_000_000,
+            owner: attacker.pubkey(),
+            data: borsh::to_vec(&fake).unwrap(),
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    let add_ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(attacker.pubkey()),
+        fake_oracle,
+        Some(attacker.pubkey()),
+    );
+    assert!(
+        send(&mut svm, add_ix, &attacker).is_err(),
+        "Oracle account must be owned by the program"
+    );
+}
+
+#[test]
+fn test_init_funded_pda() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+
+    // Anyone can send lamports to the oracle PDA before init
+    svm.set_account(
+        oracle,
+        Account {
+            lamports: 1,
+            owner: system_program::ID,
+            ..Account::default()
+        },
+    )
+    .unwrap();
+
+    let init_ix = create_init_ix(
+        program_id,
+        owner.pubkey(),
+        owner.pubkey(),
+        60,
+        -8,
+        FEED_ID,
+        3,
+    );
     let res = svm.send_transaction(Transaction::new_signed_with_payer(
         &[init_ix],
         Some(&owner.pubkey()),
         &[&owner],
         svm.latest_blockhash(),
     ));
+    assert!(res.is_ok());
 
-    assert!(
-        res.is_err(),
-        "Reinitialization should fail because oracle.owner is already set"
+    // Topped up to rent exemption and owned by the program
+    let space = history::get_space(3);
+    let account = svm.get_account(&oracle).unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.data.len(), space);
+    assert_eq!(
+        account.lamports,
+        svm.minimum_balance_for_rent_exemption(space)
     );
 
-    // Update
-    let update_ix = Instruction {
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.owner, owner.pubkey());
+    assert_eq!(oracle_state.feed_id, FEED_ID);
+}
+
+#[test]
+fn test_median() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+
+    let publishers: Vec<Keypair> = (0..Oracle::MAX_PUBLISHERS)
+        .map(|_| Keypair::new())
+        .collect();
+    for publisher in &publishers {
+        svm.airdrop(&publisher.pubkey(), 1_000_000_000).unwrap();
+    }
+
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+
+    set_clock(&mut svm, 10, 1000);
+
+    // Init
+    let max_age = 60;
+    let ix = create_init_ix(
         program_id,
-        accounts: vec![
-            AccountMeta::new(oracle.pubkey(), false),
-            AccountMeta::new(owner.pubkey(), true),
-        ],
-        data: borsh::to_vec(&Cmd::Update(1234)).unwrap(),
+        owner.pubkey(),
+        owner.pubkey(),
+        max_age,
+        -8,
+        FEED_ID,
+        0,
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    // Fill every slot
+    for publisher in &publishers {
+        let ix = create_ix(
+            program_id,
+            &Cmd::AddPublisher(publisher.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        );
+        assert!(send(&mut svm, ix, &owner).is_ok());
+    }
+
+    // No free slot
+    let ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_err());
+
+    // No submission yet
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.num_publishers, Oracle::MAX_PUBLISHERS as u8);
+    assert_eq!(oracle_state.get_aggregate(1000), None);
+
+    // Stale submission at 1000
+    let ix = create_ix(
+        program_id,
+        &Cmd::Update(500),
+        oracle,
+        Some(publishers[0].pubkey()),
+    );
+    assert!(send(&mut svm, ix, &publishers[0]).is_ok());
+
+    // Fresh submissions at 1100
+    set_clock(&mut svm, 20, 1100);
+
+    let prices = [100, 104, 90, 300];
+    for (publisher, price) in publishers[1..].iter().zip(prices) {
+        let ix = create_ix(
+            program_id,
+            &Cmd::Update(price),
+            oracle,
+            Some(publisher.pubkey()),
+        );
+        assert!(send(&mut svm, ix, publisher).is_ok());
+    }
+
+    // Median of 90, 100, 104, 300 = (100 + 104) / 2
+    // Confidence = (300 - 90) / 2
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 102);
+    assert_eq!(oracle_state.confidence, 105);
+    assert_eq!(oracle_state.last_update_slot, 20);
+    assert_eq!(oracle_state.last_update_ts, 1100);
+    assert_eq!(oracle_state.get_aggregate(1100), Some((102, 105, 1100)));
+    // Every submission is stale
+    assert_eq!(oracle_state.get_aggregate(1100 + max_age + 1), None);
+
+    // Remove publisher of 300, median of 90, 100, 104
+    let ix = create_ix(
+        program_id,
+        &Cmd::RemovePublisher(publishers[4].pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(
+        oracle_state.num_publishers,
+        Oracle::MAX_PUBLISHERS as u8 - 1
+    );
+    assert_eq!(oracle_state.price, 100);
+    assert_eq!(oracle_state.confidence, 7);
+
+    // Removed publisher can't submit
+    let ix = create_ix(
+        program_id,
+        &Cmd::Update(1),
+        oracle,
+        Some(publishers[4].pubkey()),
+    );
+    assert!(send(&mut svm, ix, &publishers[4]).is_err());
+
+    // Remove by a publisher
+    let ix = create_ix(
+        program_id,
+        &Cmd::RemovePublisher(publishers[1].pubkey()),
+        oracle,
+        Some(publishers[1].pubkey()),
+    );
+    assert!(send(&mut svm, ix, &publishers[1]).is_err());
+}
+
+#[test]
+fn test_get_price_no_older_than() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
     };
 
-    svm.send_transaction(Transaction::new_signed_with_payer(
-        &[update_ix],
-        Some(&owner.pubkey()),
-        &[&owner],
-        svm.latest_blockhash(),
-    ))
-    .unwrap();
+    set_clock(&mut svm, 10, 1000);
 
-    let data = svm.get_account(&oracle.pubkey()).unwrap().data;
-    let oracle_state = Oracle::try_from_slice(&data).unwrap();
-    assert_eq!(oracle_state.price, 1234);
+    let ixs = [
+        create_init_ix(
+            program_id,
+            owner.pubkey(),
+            owner.pubkey(),
+            60,
+            -6,
+            FEED_ID,
+            0,
+        ),
+        create_ix(
+            program_id,
+            &Cmd::AddPublisher(owner.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+        create_ix(
+            program_id,
+            &Cmd::Update(2_500_000),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+    ];
+    for ix in ixs {
+        assert!(send(&mut svm, ix, &owner).is_ok());
+    }
 
-    // Invalid update (by attacker)
-    let update_ix = Instruction {
+    let oracle_state = get_oracle(&svm, &oracle);
+    let price = Price {
+        price: 2_500_000,
+        confidence: 0,
+        exponent: -6,
+        timestamp: 1000,
+    };
+
+    assert_eq!(oracle_state.get_price_no_older_than(1000, 0), Ok(price));
+    assert_eq!(oracle_state.get_price_no_older_than(1030, 30), Ok(price));
+    assert_eq!(
+        oracle_state.get_price_no_older_than(1031, 30),
+        Err(OracleError::StalePrice.into())
+    );
+
+    // 3 tokens with 9 decimals at $2.5, with 6 decimals
+    assert_eq!(price.get_value(3_000_000_000, 9, 6), Some(7_500_000));
+
+    // Valued at price - confidence
+    let wide = Price {
+        confidence: 100_000,
+        ..price
+    };
+    assert_eq!(wide.get_value(3_000_000_000, 9, 6), Some(7_200_000));
+    assert_eq!(wide.get_value(u64::MAX, 0, 18), None);
+
+    // GetPrice returns the price to programs calling through CPI
+    set_clock(&mut svm, 11, 1030);
+    let ix = create_ix(program_id, &Cmd::GetPrice(30), oracle, None);
+    let res = send(&mut svm, ix, &owner).unwrap();
+    assert_eq!(res.return_data.program_id, program_id);
+    assert_eq!(Price::try_from_slice(&res.return_data.data).unwrap(), price);
+
+    let ix = create_ix(program_id, &Cmd::GetPrice(29), oracle, None);
+    assert_oracle_error(send(&mut svm, ix, &owner), OracleError::StalePrice);
+}
+
+#[test]
+fn test_owner() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    for user in [&owner, &alice, &bob] {
+        svm.airdrop(&user.pubkey(), 1_000_000_000).unwrap();
+    }
+
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+    let propose_ix = |new_owner: Pubkey, signer: Pubkey| {
+        create_ix(
+            program_id,
+            &Cmd::ProposeOwner(new_owner),
+            oracle,
+            Some(signer),
+        )
+    };
+    let accept_ix = |signer: Pubkey| {
+        create_ix(program_id, &Cmd::AcceptOwner, oracle, Some(signer))
+    };
+
+    // Init
+    let ix = create_init_ix(
         program_id,
-        accounts: vec![
-            AccountMeta::new(oracle.pubkey(), false),
-            AccountMeta::new(attacker.pubkey(), true),
-        ],
-        data: borsh::to_vec(&Cmd::Update(9999)).unwrap(),
+        owner.pubkey(),
+        owner.pubkey(),
+        60,
+        -8,
+        FEED_ID,
+        0,
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    // Propose by a key that is not the owner
+    let ix = propose_ix(alice.pubkey(), alice.pubkey());
+    assert!(send(&mut svm, ix, &alice).is_err());
+
+    // Accept without a proposal
+    let ix = accept_ix(alice.pubkey());
+    assert!(send(&mut svm, ix, &alice).is_err());
+
+    // Propose alice
+    let ix = propose_ix(alice.pubkey(), owner.pubkey());
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.owner, owner.pubkey());
+    assert_eq!(oracle_state.pending_owner, alice.pubkey());
+
+    // Accept by a key that is not the pending owner
+    let ix = accept_ix(bob.pubkey());
+    assert!(send(&mut svm, ix, &bob).is_err());
+
+    // Overwrite with bob
+    let ix = propose_ix(bob.pubkey(), owner.pubkey());
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.pending_owner, bob.pubkey());
+
+    // Alice is no longer pending
+    let ix = accept_ix(alice.pubkey());
+    assert!(send(&mut svm, ix, &alice).is_err());
+
+    // Bob accepts
+    let ix = accept_ix(bob.pubkey());
+    assert!(send(&mut svm, ix, &bob).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.owner, bob.pubkey());
+    assert_eq!(oracle_state.pending_owner, Pubkey::default());
+
+    // Old owner can't manage the oracle
+    let ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_err());
+
+    let ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(bob.pubkey()),
+        oracle,
+        Some(bob.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &bob).is_ok());
+
+    // Bob proposes alice, then cancels
+    let ix = propose_ix(alice.pubkey(), bob.pubkey());
+    assert!(send(&mut svm, ix, &bob).is_ok());
+
+    let ix = propose_ix(Pubkey::default(), bob.pubkey());
+    assert!(send(&mut svm, ix, &bob).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.pending_owner, Pubkey::default());
+
+    let ix = accept_ix(alice.pubkey());
+    assert!(send(&mut svm, ix, &alice).is_err());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.owner, bob.pubkey());
+}
+
+#[test]
+fn test_history() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
     };
 
-    let res = svm.send_transaction(Transaction::new_signed_with_payer(
-        &[update_ix],
-        Some(&attacker.pubkey()),
-        &[&attacker],
-        svm.latest_blockhash(),
-    ));
+    // Capacity too large
+    let ix = create_init_ix(
+        program_id,
+        owner.pubkey(),
+        owner.pubkey(),
+        60,
+        -8,
+        FEED_ID,
+        history::MAX_CAPACITY + 1,
+    );
+    assert!(send(&mut svm, ix, &owner).is_err());
 
-    assert!(
-        res.is_err(),
-        "Unauthorized signer should not be able to update oracle"
+    // Max capacity fits in an account created through CPI
+    let ix = create_init_ix(
+        program_id,
+        owner.pubkey(),
+        owner.pubkey(),
+        60,
+        -8,
+        FEED_ID + 1,
+        history::MAX_CAPACITY,
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+    let (max_oracle, _) =
+        get_oracle_pda(program_id, &owner.pubkey(), FEED_ID + 1);
+    let account = svm.get_account(&max_oracle).unwrap();
+    assert_eq!(
+        account.data.len(),
+        history::get_space(history::MAX_CAPACITY)
     );
+    assert!(account.data.len() <= history::MAX_SPACE);
 
-    let data = svm.get_account(&oracle.pubkey()).unwrap().data;
-    let oracle_state = Oracle::try_from_slice(&data).unwrap();
-    assert_eq!(oracle_state.price, 1234);
+    let ixs = [
+        create_init_ix(
+            program_id,
+            owner.pubkey(),
+            owner.pubkey(),
+            60,
+            -8,
+            FEED_ID,
+            3,
+        ),
+        create_ix(
+            program_id,
+            &Cmd::AddPublisher(owner.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+    ];
+    for ix in ixs {
+        assert!(send(&mut svm, ix, &owner).is_ok());
+    }
+
+    let account = svm.get_account(&oracle).unwrap();
+    assert_eq!(account.data.len(), history::get_space(3));
+
+    let history = History::load(&account.data).unwrap();
+    assert_eq!({ history.header.capacity }, 3);
+    assert_eq!(history.last(), None);
+
+    // Price at the same timestamp replaces the previous one,
+    // 1000 is overwritten once the buffer is full
+    let updates = [
+        (1000, 100),
+        (1010, 200),
+        (1010, 210),
+        (1030, 300),
+        (1040, 400),
+    ];
+    for (i, (t, price)) in updates.into_iter().enumerate() {
+        set_clock(&mut svm, i as u64 + 1, t);
+        let ix = create_ix(
+            program_id,
+            &Cmd::Update(price),
+            oracle,
+            Some(owner.pubkey()),
+        );
+        assert!(send(&mut svm, ix, &owner).is_ok());
+    }
+
+    let account = svm.get_account(&oracle).unwrap();
+    let history = History::load(&account.data).unwrap();
+
+    assert_eq!(
+        history.iter().collect::<Vec<_>>(),
+        vec![
+            PriceEntry {
+                timestamp: 1010,
+                price: 210
+            },
+            PriceEntry {
+                timestamp: 1030,
+                price: 300
+            },
+            PriceEntry {
+                timestamp: 1040,
+                price: 400
+            },
+        ]
+    );
+
+    // (210 * 20 + 300 * 10 + 400 * 10) / 40
+    assert_eq!(history.get_twap(1050, 40), Some(280));
+    // 400 in effect for the whole window
+    assert_eq!(history.get_twap(1050, 10), Some(400));
+    // No price before 1010
+    assert_eq!(history.get_twap(1000, 10), None);
+
+    assert_eq!(history.get_min_max(1050, 40), Some((210, 400)));
+    assert_eq!(history.get_min_max(1050, 15), Some((400, 400)));
+    assert_eq!(history.get_min_max(1050, 5), None);
+
+    // Oracle is still readable with history after it
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 400);
+}
+
+#[test]
+fn test_limits() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let publisher = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&publisher.pubkey(), 1_000_000_000).unwrap();
+
+    let send = |svm: &mut LiteSVM, ix: Instruction, signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+    let update = |svm: &mut LiteSVM, t: i64, price: u64| {
+        set_clock(svm, t as u64, t);
+        let ix = create_ix(
+            program_id,
+            &Cmd::Update(price),
+            oracle,
+            Some(publisher.pubkey()),
+        );
+        send(svm, ix, &publisher)
+    };
+
+    let ixs = [
+        create_init_ix(
+            program_id,
+            owner.pubkey(),
+            owner.pubkey(),
+            60,
+            -8,
+            FEED_ID,
+            10,
+        ),
+        create_ix(
+            program_id,
+            &Cmd::AddPublisher(publisher.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+    ];
+    for ix in ixs {
+        assert!(send(&mut svm, ix, &owner).is_ok());
+    }
+
+    // Set limits by a key that is not the owner
+    let ix = create_ix(
+        program_id,
+        &Cmd::SetLimits(1000, 10),
+        oracle,
+        Some(publisher.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &publisher).is_err());
+
+    // Max 10% change, 10 seconds between submissions
+    let ix = create_ix(
+        program_id,
+        &Cmd::SetLimits(1000, 10),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.max_deviation_bps, 1000);
+    assert_eq!(oracle_state.min_interval, 10);
+
+    // First price, no price to compare with
+    assert!(update(&mut svm, 1000, 100).is_ok());
+
+    // Too soon
+    assert_oracle_error(
+        update(&mut svm, 1009, 105),
+        OracleError::UpdateTooFrequent,
+    );
+
+    // +10%
+    assert!(update(&mut svm, 1010, 110).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 110);
+
+    // -50%, halts the oracle and keeps the price
+    assert!(update(&mut svm, 1020, 55).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert!(oracle_state.halted);
+    assert_eq!(oracle_state.price, 110);
+    assert_eq!(oracle_state.last_update_ts, 1010);
+    assert_eq!(
+        oracle_state.get_price_no_older_than(1020, 60),
+        Err(OracleError::Halted.into())
+    );
+
+    let account = svm.get_account(&oracle).unwrap();
+    let history = History::load(&account.data).unwrap();
+    assert_eq!(history.get_min_max(1020, 60), Some((100, 110)));
+
+    // Updates are rejected while halted
+    assert_oracle_error(update(&mut svm, 1030, 110), OracleError::Halted);
+
+    // Resume by a key that is not the owner
+    let ix =
+        create_ix(program_id, &Cmd::Resume, oracle, Some(publisher.pubkey()));
+    assert!(send(&mut svm, ix, &publisher).is_err());
+
+    // Owner resumes, the fat-fingered price was not stored
+    let ix = create_ix(program_id, &Cmd::Resume, oracle, Some(owner.pubkey()));
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert!(!oracle_state.halted);
+    assert_eq!(oracle_state.price, 110);
+    assert_eq!(oracle_state.last_update_ts, 1030);
+    assert_eq!(oracle_state.publishers[0].price, 110);
+    assert_eq!(oracle_state.publishers[0].timestamp, 1010);
+
+    // Not halted
+    let ix = create_ix(program_id, &Cmd::Resume, oracle, Some(owner.pubkey()));
+    assert!(send(&mut svm, ix, &owner).is_err());
+
+    // Limits still apply from the last accepted price
+    assert!(update(&mut svm, 1040, 60).is_ok());
+    assert!(get_oracle(&svm, &oracle).halted);
+
+    let ix = create_ix(program_id, &Cmd::Resume, oracle, Some(owner.pubkey()));
+    assert!(send(&mut svm, ix, &owner).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 110);
+
+    // Owner accepts a large move by removing the deviation limit
+    let ix = create_ix(
+        program_id,
+        &Cmd::SetLimits(0, 10),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+    assert!(update(&mut svm, 1050, 60).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 60);
+
+    // min_interval applies to the oracle, not to each publisher
+    let ix = create_ix(
+        program_id,
+        &Cmd::AddPublisher(owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let owner_update = |svm: &mut LiteSVM, t: i64, price: u64| {
+        set_clock(svm, t as u64, t);
+        let ix = create_ix(
+            program_id,
+            &Cmd::Update(price),
+            oracle,
+            Some(owner.pubkey()),
+        );
+        send(svm, ix, &owner)
+    };
+    assert_oracle_error(
+        owner_update(&mut svm, 1055, 200),
+        OracleError::UpdateTooFrequent,
+    );
+    // Median of 60, 200
+    assert!(owner_update(&mut svm, 1060, 200).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 130);
+
+    // Removing a publisher moves the price by more than max_deviation_bps
+    // without halting the oracle
+    let ix = create_ix(
+        program_id,
+        &Cmd::SetLimits(1000, 10),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+    let ix = create_ix(
+        program_id,
+        &Cmd::RemovePublisher(owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, ix, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert!(!oracle_state.halted);
+    assert_eq!(oracle_state.price, 60);
+}
+
+#[test]
+fn test_update_with_signature() {
+    let mut svm = LiteSVM::new();
+
+    let owner = Keypair::new();
+    let publisher = Keypair::new();
+    let relayer = Keypair::new();
+    let program_id = setup(&mut svm);
+    let (oracle, _) = get_oracle_pda(program_id, &owner.pubkey(), FEED_ID);
+
+    svm.airdrop(&owner.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&relayer.pubkey(), 1_000_000_000).unwrap();
+
+    let send = |svm: &mut LiteSVM, ixs: &[Instruction], signer: &Keypair| {
+        let res = svm.send_transaction(Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ));
+        svm.expire_blockhash();
+        res
+    };
+    // Report signed by signer, sent by relayer
+    let submit = |svm: &mut LiteSVM, report: &PriceReport, signer: &Keypair| {
+        let ixs = report::create_update_with_signature_ixs(
+            &program_id,
+            report,
+            signer,
+        );
+        send(svm, &ixs, &relayer)
+    };
+
+    let ixs = [
+        create_init_ix(
+            program_id,
+            owner.pubkey(),
+            owner.pubkey(),
+            60,
+            -8,
+            FEED_ID,
+            10,
+        ),
+        create_ix(
+            program_id,
+            &Cmd::AddPublisher(publisher.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+    ];
+    assert!(send(&mut svm, &ixs, &owner).is_ok());
+
+    set_clock(&mut svm, 100, 1000);
+
+    // Publisher has no SOL, relayer pays
+    let report = PriceReport {
+        feed: oracle,
+        price: 100,
+        timestamp: 990,
+        nonce: 1,
+    };
+    assert!(submit(&mut svm, &report, &publisher).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 100);
+    assert_eq!(oracle_state.last_update_slot, 100);
+    assert_eq!(oracle_state.last_update_ts, 1000);
+    // Stored with the report timestamp
+    assert_eq!(oracle_state.publishers[0].timestamp, 990);
+    assert_eq!(oracle_state.get_aggregate(1000), Some((100, 0, 990)));
+    assert_eq!(oracle_state.publishers[0].nonce, 1);
+
+    let account = svm.get_account(&oracle).unwrap();
+    let history = History::load(&account.data).unwrap();
+    assert_eq!(
+        history.last(),
+        Some(PriceEntry {
+            timestamp: 1000,
+            price: 100
+        })
+    );
+
+    // Replayed
+    assert_oracle_error(
+        submit(&mut svm, &report, &publisher),
+        OracleError::ReplayedReport,
+    );
+
+    // Signed by the owner, aggregated with the publisher's price
+    let owner_report = PriceReport {
+        price: 104,
+        timestamp: 992,
+        ..report.clone()
+    };
+    assert!(submit(&mut svm, &owner_report, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 102);
+    assert_eq!(oracle_state.confidence, 2);
+    assert_eq!(oracle_state.owner_submission.price, 104);
+    assert_eq!(oracle_state.owner_submission.nonce, 1);
+    assert_eq!(oracle_state.publishers[0].price, 100);
+
+    assert_oracle_error(
+        submit(&mut svm, &owner_report, &owner),
+        OracleError::ReplayedReport,
+    );
+
+    // Same nonce, newer timestamp
+    let replayed = PriceReport {
+        price: 200,
+        timestamp: 995,
+        ..report.clone()
+    };
+    assert_oracle_error(
+        submit(&mut svm, &replayed, &publisher),
+        OracleError::ReplayedReport,
+    );
+
+    // Newer nonce, older timestamp
+    let replayed = PriceReport {
+        price: 200,
+        timestamp: 980,
+        nonce: 2,
+        ..report.clone()
+    };
+    assert_oracle_error(
+        submit(&mut svm, &replayed, &publisher),
+        OracleError::ReplayedReport,
+    );
+
+    // Another oracle
+    let other = PriceReport {
+        feed: Pubkey::new_unique(),
+        timestamp: 995,
+        nonce: 2,
+        ..report.clone()
+    };
+    let mut ixs = report::create_update_with_signature_ixs(
+        &program_id,
+        &other,
+        &publisher,
+    );
+    ixs[1].accounts[0].pubkey = oracle;
+    assert_oracle_error(
+        send(&mut svm, &ixs, &relayer),
+        OracleError::InvalidReport,
+    );
+
+    // In the future
+    let future = PriceReport {
+        timestamp: 1001,
+        nonce: 2,
+        ..report.clone()
+    };
+    assert_oracle_error(
+        submit(&mut svm, &future, &publisher),
+        OracleError::InvalidReport,
+    );
+
+    // Older than max_age
+    set_clock(&mut svm, 200, 1100);
+    let old = PriceReport {
+        timestamp: 1039,
+        nonce: 2,
+        ..report.clone()
+    };
+    assert_oracle_error(
+        submit(&mut svm, &old, &publisher),
+        OracleError::InvalidReport,
+    );
+
+    // Signed by a key that is not a publisher
+    let next = PriceReport {
+        price: 110,
+        timestamp: 1090,
+        nonce: 2,
+        ..report.clone()
+    };
+    assert!(submit(&mut svm, &next, &relayer).is_err());
+
+    // Signature does not match the report
+    let mut ixs = report::create_update_with_signature_ixs(
+        &program_id,
+        &next,
+        &publisher,
+    );
+    let n = ixs[0].data.len();
+    ixs[0].data[n - 1] ^= 1;
+    assert!(send(&mut svm, &ixs, &relayer).is_err());
+
+    // Without the ed25519 instruction
+    let ixs = report::create_update_with_signature_ixs(
+        &program_id,
+        &next,
+        &publisher,
+    );
+    assert_oracle_error(
+        send(&mut svm, &ixs[1..], &relayer),
+        OracleError::InvalidSignature,
+    );
+
+    // Nonces do not have to be consecutive
+    let next = PriceReport { nonce: 5, ..next };
+    assert!(submit(&mut svm, &next, &publisher).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.price, 110);
+    assert_eq!(oracle_state.publishers[0].nonce, 5);
+    assert_eq!(oracle_state.max_nonce, 5);
+
+    // Remove and add the publisher again
+    let ixs = [
+        create_ix(
+            program_id,
+            &Cmd::RemovePublisher(publisher.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+        create_ix(
+            program_id,
+            &Cmd::AddPublisher(publisher.pubkey()),
+            oracle,
+            Some(owner.pubkey()),
+        ),
+    ];
+    assert!(send(&mut svm, &ixs, &owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert!(!oracle_state.publishers[0].has_price);
+    assert_eq!(oracle_state.publishers[0].nonce, 5);
+
+    // Reports signed before removal are not replayed
+    assert_oracle_error(
+        submit(&mut svm, &next, &publisher),
+        OracleError::ReplayedReport,
+    );
+
+    // New reports continue above the highest nonce
+    let next = PriceReport {
+        price: 120,
+        timestamp: 1095,
+        nonce: 6,
+        ..next
+    };
+    assert!(submit(&mut svm, &next, &publisher).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 120);
+
+    // Transfer ownership
+    let new_owner = Keypair::new();
+    svm.airdrop(&new_owner.pubkey(), 1_000_000_000).unwrap();
+    let ix = create_ix(
+        program_id,
+        &Cmd::ProposeOwner(new_owner.pubkey()),
+        oracle,
+        Some(owner.pubkey()),
+    );
+    assert!(send(&mut svm, &[ix], &owner).is_ok());
+    let ix = create_ix(
+        program_id,
+        &Cmd::AcceptOwner,
+        oracle,
+        Some(new_owner.pubkey()),
+    );
+    assert!(send(&mut svm, &[ix], &new_owner).is_ok());
+
+    let oracle_state = get_oracle(&svm, &oracle);
+    assert_eq!(oracle_state.owner_submission.key, new_owner.pubkey());
+    assert!(!oracle_state.owner_submission.has_price);
+    assert_eq!(oracle_state.owner_submission.nonce, 6);
+
+    // Previous owner
+    let owner_report = PriceReport {
+        price: 130,
+        timestamp: 1096,
+        nonce: 7,
+        ..next
+    };
+    assert!(submit(&mut svm, &owner_report, &owner).is_err());
+
+    // New owner starts at the highest nonce
+    let replayed = PriceReport {
+        nonce: 6,
+        ..owner_report.clone()
+    };
+    assert_oracle_error(
+        submit(&mut svm, &replayed, &new_owner),
+        OracleError::ReplayedReport,
+    );
+    assert!(submit(&mut svm, &owner_report, &new_owner).is_ok());
+    assert_eq!(get_oracle(&svm, &oracle).price, 125);
 }